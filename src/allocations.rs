@@ -0,0 +1,133 @@
+//! Allocation counting
+//!
+//! With the `count-allocations` cargo feature, a global allocator counts the
+//! allocations, deallocations and bytes allocated by the current thread while
+//! counting is enabled, which is only inside the timed regions (see
+//! [`crate::timed_region`]). The counters are thread local, so allocations by
+//! other threads (such as tokio workers) are not counted.
+//!
+//! Without the feature the system allocator is used and nothing is counted.
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+/// True if allocations are counted (the `count-allocations` feature is enabled)
+pub const ENABLED: bool = cfg!(feature = "count-allocations");
+
+/// Allocations made while counting was enabled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocationCounts {
+    /// number of allocations (a reallocation counts as one allocation and one
+    /// deallocation)
+    pub allocations: u64,
+    pub deallocations: u64,
+    /// total bytes requested by the allocations
+    pub bytes: u64,
+}
+
+impl AllocationCounts {
+    const ZERO: Self = Self {
+        allocations: 0,
+        deallocations: 0,
+        bytes: 0,
+    };
+
+    /// Average counts of `runs` runs
+    pub fn per_run(&self, runs: usize) -> Self {
+        let runs = runs.max(1) as u64;
+        Self {
+            allocations: self.allocations / runs,
+            deallocations: self.deallocations / runs,
+            bytes: self.bytes / runs,
+        }
+    }
+}
+
+impl AddAssign for AllocationCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.allocations += other.allocations;
+        self.deallocations += other.deallocations;
+        self.bytes += other.bytes;
+    }
+}
+
+// const initialized, so accessing them does not allocate
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static COUNTS: Cell<AllocationCounts> = const { Cell::new(AllocationCounts::ZERO) };
+}
+
+/// Enables or disables counting on the current thread
+pub fn set_counting(counting: bool) {
+    COUNTING.with(|cell| cell.set(counting));
+}
+
+/// Returns the counts of the current thread and resets them to zero
+pub fn take() -> AllocationCounts {
+    COUNTS.with(|cell| cell.replace(AllocationCounts::ZERO))
+}
+
+#[cfg(feature = "count-allocations")]
+fn record(f: impl FnOnce(&mut AllocationCounts)) {
+    // try_with, as the thread locals may already be destroyed when a thread exits
+    let counting = COUNTING.try_with(|cell| cell.get()).unwrap_or(false);
+    if counting {
+        let _ = COUNTS.try_with(|cell| {
+            let mut counts = cell.get();
+            f(&mut counts);
+            cell.set(counts);
+        });
+    }
+}
+
+#[cfg(feature = "count-allocations")]
+mod allocator {
+    use super::record;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    /// The system allocator, counting the allocations of the current thread
+    struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                record(|counts| {
+                    counts.allocations += 1;
+                    counts.bytes += layout.size() as u64;
+                });
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() {
+                record(|counts| {
+                    counts.allocations += 1;
+                    counts.bytes += layout.size() as u64;
+                });
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            record(|counts| counts.deallocations += 1);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                record(|counts| {
+                    counts.allocations += 1;
+                    counts.deallocations += 1;
+                    counts.bytes += new_size as u64;
+                });
+            }
+            new_ptr
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+}
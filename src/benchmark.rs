@@ -1,5 +1,15 @@
+use crate::allocations::{self, AllocationCounts};
+use crate::encryption::decryption_properties;
+use crate::footer;
+use crate::parquet_file::ParquetFileSpec;
+use crate::perf_counters::{self, PerfCounts, Phase, PhaseCounts};
+use crate::tail_bytes::TailBytes;
+use crate::timed_region::TimedRegion;
+use crate::writer_options::{Encryption, WriterCrate, WriterVersion};
 use bytes::Bytes;
 use comfy_table::Table;
+use serde::Deserialize;
+use std::cell::RefCell;
 use std::fmt::{Debug, Display};
 use std::fs;
 use std::io::{Read, Seek};
@@ -7,14 +17,264 @@ use std::ops::Range;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// Metadata decoders that can be benchmarked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decoder {
+    /// Released parquet 56 (decoder generated by the thrift compiler)
+    Arrow56,
+    /// Released parquet 57 (custom thrift decoder)
+    Arrow57,
+    /// parquet 57 modified to skip parsing statistics and page indexes
+    Arrow57NoStats,
+    /// Released parquet 57 decoding a copy of the footer, re-encoded by the
+    /// parquet 57 writer with statistics (and column indexes) for only the given
+    /// percentage of the columns. `100` is the re-encoded footer with all the
+    /// statistics, the baseline the other percentages are compared to
+    Arrow57Projected(usize),
+    /// The "no stats" fork decoding the footer re-encoded with all the
+    /// statistics (the `Arrow57Projected(100)` footer), the best case the
+    /// projected footers are compared to
+    Arrow57NoStatsReencoded,
+    /// Released parquet 56 `ParquetMetaDataReader`, reading from preloaded bytes
+    Arrow56MetadataReader,
+    /// Released parquet 57 `ParquetMetaDataReader`, reading from preloaded bytes
+    Arrow57MetadataReader,
+    /// Released parquet 56 `SerializedFileReader`, reading from preloaded bytes
+    Arrow56SerializedFileReader,
+    /// Released parquet 57 `SerializedFileReader`, reading from preloaded bytes
+    Arrow57SerializedFileReader,
+}
+
+impl Display for Decoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Decoder::Arrow56 => write!(f, "Arrow 56"),
+            Decoder::Arrow57 => write!(f, "Arrow 57"),
+            Decoder::Arrow57NoStats => write!(f, "Arrow 57 (no stats)"),
+            Decoder::Arrow57Projected(percent) => {
+                write!(f, "Arrow 57 (re-encoded, {percent}% stats)")
+            }
+            Decoder::Arrow57NoStatsReencoded => write!(f, "Arrow 57 (re-encoded, no stats)"),
+            Decoder::Arrow56MetadataReader => write!(f, "Arrow 56 (ParquetMetaDataReader)"),
+            Decoder::Arrow57MetadataReader => write!(f, "Arrow 57 (ParquetMetaDataReader)"),
+            Decoder::Arrow56SerializedFileReader => write!(f, "Arrow 56 (SerializedFileReader)"),
+            Decoder::Arrow57SerializedFileReader => write!(f, "Arrow 57 (SerializedFileReader)"),
+        }
+    }
+}
+
+impl Decoder {
+    /// Returns true for the decoders compared in the projection table: released
+    /// Arrow 57 with all and with projected statistics, and the "no stats" fork
+    /// on the original and the re-encoded footer
+    fn in_projection_table(&self) -> bool {
+        matches!(
+            self,
+            Decoder::Arrow57
+                | Decoder::Arrow57Projected(_)
+                | Decoder::Arrow57NoStats
+                | Decoder::Arrow57NoStatsReencoded
+        )
+    }
+
+    /// Returns true for the push decoders whose round trips are counted in the
+    /// prefetch sweep (the projected decoder is the released parquet 57 one)
+    pub fn counts_round_trips(&self) -> bool {
+        matches!(
+            self,
+            Decoder::Arrow56 | Decoder::Arrow57 | Decoder::Arrow57NoStats
+        )
+    }
+
+    /// Returns true for the decoders that can be run on encrypted files. Only
+    /// `ParquetMetaDataReader` is given the keys: the push decoders can not
+    /// decrypt (the option is not public), and neither is `SerializedFileReader`
+    pub fn reads_encrypted(&self) -> bool {
+        matches!(
+            self,
+            Decoder::Arrow56MetadataReader | Decoder::Arrow57MetadataReader
+        )
+    }
+
+    /// The push decoder of the same crate, for the synchronous readers
+    pub fn push_decoder(&self) -> Option<Decoder> {
+        match self {
+            Decoder::Arrow56MetadataReader | Decoder::Arrow56SerializedFileReader => {
+                Some(Decoder::Arrow56)
+            }
+            Decoder::Arrow57MetadataReader | Decoder::Arrow57SerializedFileReader => {
+                Some(Decoder::Arrow57)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns false for the decoders that load the page indexes in the same call
+    /// as the metadata (`SerializedFileReader`), so their parse time can not be
+    /// measured separately
+    fn times_page_indexes(&self) -> bool {
+        !matches!(
+            self,
+            Decoder::Arrow56SerializedFileReader | Decoder::Arrow57SerializedFileReader
+        )
+    }
+
+    /// Returns false for page index policies the decoder can not load.
+    /// `SerializedFileReader` and the parquet 56 push decoder load either both
+    /// page indexes or neither
+    pub fn supports(&self, policy: PageIndexPolicy) -> bool {
+        match self {
+            Decoder::Arrow56
+            | Decoder::Arrow56SerializedFileReader
+            | Decoder::Arrow57SerializedFileReader => {
+                matches!(policy, PageIndexPolicy::Both | PageIndexPolicy::None)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Which page indexes the decoder loads after the metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageIndexPolicy {
+    /// Column index and offset index
+    #[default]
+    Both,
+    /// Column index only
+    ColumnIndex,
+    /// Offset index only (most queries only need the offset index)
+    OffsetIndex,
+    /// No page indexes
+    None,
+}
+
+impl Display for PageIndexPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageIndexPolicy::Both => write!(f, "column and offset index"),
+            PageIndexPolicy::ColumnIndex => write!(f, "column index only"),
+            PageIndexPolicy::OffsetIndex => write!(f, "offset index only"),
+            PageIndexPolicy::None => write!(f, "no page index"),
+        }
+    }
+}
+
+impl PageIndexPolicy {
+    pub fn column_index(&self) -> bool {
+        matches!(self, PageIndexPolicy::Both | PageIndexPolicy::ColumnIndex)
+    }
+
+    pub fn offset_index(&self) -> bool {
+        matches!(self, PageIndexPolicy::Both | PageIndexPolicy::OffsetIndex)
+    }
+
+    /// Name of the page index parse time in table headers
+    fn index_label(&self) -> &'static str {
+        match self {
+            PageIndexPolicy::Both => "PageIndex (Column/Offset)",
+            PageIndexPolicy::ColumnIndex => "Column Index",
+            PageIndexPolicy::OffsetIndex => "Offset Index",
+            PageIndexPolicy::None => "PageIndex (none)",
+        }
+    }
+}
+
+/// A decoder and the page indexes it loads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoderConfig {
+    pub decoder: Decoder,
+    pub page_index_policy: PageIndexPolicy,
+}
+
+/// The decoders of `configs`, in order and without duplicates
+pub fn distinct_decoders(configs: &[DecoderConfig]) -> Vec<Decoder> {
+    let mut decoders = vec![];
+    for config in configs {
+        if !decoders.contains(&config.decoder) {
+            decoders.push(config.decoder);
+        }
+    }
+    decoders
+}
+
+impl DecoderConfig {
+    /// Returns true if the page indexes are loaded in the same call as the
+    /// metadata, so only the combined time is measured (`SerializedFileReader`)
+    fn combined_index_time(&self) -> bool {
+        self.page_index_policy != PageIndexPolicy::None && !self.decoder.times_page_indexes()
+    }
+
+    /// Label of the metadata parse time
+    fn metadata_label(&self) -> &'static str {
+        if self.combined_index_time() {
+            "Metadata + PageIndex"
+        } else {
+            "Metadata"
+        }
+    }
+
+    /// Label of the page index parse time
+    fn index_label(&self) -> &'static str {
+        if self.combined_index_time() {
+            "PageIndex (in Metadata)"
+        } else {
+            self.page_index_policy.index_label()
+        }
+    }
+
+    /// Formats the average page index parse time of `timing`, or `-` if it is
+    /// included in the metadata parse time
+    fn index_cell(&self, timing: &Timing) -> String {
+        if self.combined_index_time() {
+            "-".to_string()
+        } else {
+            format!("{:?}", timing.avg_index_parsing_duration())
+        }
+    }
+}
+
+impl Display for DecoderConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.decoder)?;
+        // only mention non default policies to keep headers short
+        if self.page_index_policy != PageIndexPolicy::Both {
+            write!(f, " [{}]", self.page_index_policy)?;
+        }
+        Ok(())
+    }
+}
+
 /// metadata parsing benchmark function
 /// Given a filename:
 /// Loads the metadata and page indexes into memory
 /// Then times how long it takes to parse the metadata and page indexes
 pub struct MetadataParseBenchmark {
     description: String,
+    /// writer version used to write the file
+    writer_version: WriterVersion,
+    /// parquet crate used to write the file
+    writer_crate: WriterCrate,
+    /// number of columns in the file
+    columns: usize,
+    /// number of row groups in the file
+    row_groups: usize,
+    /// seed of the generated data
+    seed: u64,
+    /// parquet modular encryption of the file
+    encryption: Encryption,
+    /// names of the columns, for the column keys of encrypted files (empty for
+    /// files that are not encrypted)
+    column_names: Vec<String>,
     /// number of times to parse the footer
     num_runs: usize,
+    /// number of times to parse the footer before timing
+    warmup_runs: usize,
+    /// sizes of the initial read from the end of the file, for counting round trips
+    prefetch_hints: Vec<u64>,
+    /// parquet crates whose metadata writer is timed re-encoding the metadata
+    metadata_writers: Vec<WriterCrate>,
     /// path to the parquet file
     file_path: PathBuf,
     file_len: u64,
@@ -38,8 +298,7 @@ impl Debug for MetadataParseBenchmark {
 }
 
 /// Macro that pushes the specified byte range and bytes to the decoder
-macro_rules! push_range
-{
+macro_rules! push_range {
     ($decoder:expr, $range:expr, $bytes:expr) => {
         $decoder
             .push_ranges(vec![$range.clone()], vec![$bytes.clone()])
@@ -47,6 +306,418 @@ macro_rules! push_range
     };
 }
 
+/// Macro that returns the `ParquetMetaDataPushDecoder` `$decoder` of the
+/// `$parquet` crate configured to load the page indexes selected by `$policy`
+/// (`Optional`, as some files have no column index for some columns). The
+/// parquet 56 push decoder can only load both page indexes or neither
+macro_rules! with_page_index_policy {
+    (parquet_56, $decoder:expr, $policy:expr) => {{
+        use parquet_56::file::metadata::PageIndexPolicy as ParquetPageIndexPolicy;
+        let policy: PageIndexPolicy = $policy;
+        assert!(
+            Decoder::Arrow56.supports(policy),
+            "{policy} is not supported by Arrow 56"
+        );
+        let page_index_policy = if policy == PageIndexPolicy::None {
+            ParquetPageIndexPolicy::Skip
+        } else {
+            ParquetPageIndexPolicy::Optional
+        };
+        $decoder.with_page_index_policy(page_index_policy)
+    }};
+    ($parquet:ident, $decoder:expr, $policy:expr) => {{
+        use $parquet::file::metadata::PageIndexPolicy as ParquetPageIndexPolicy;
+        let policy: PageIndexPolicy = $policy;
+        let page_index_policy = |load| {
+            if load {
+                ParquetPageIndexPolicy::Optional
+            } else {
+                ParquetPageIndexPolicy::Skip
+            }
+        };
+        $decoder
+            .with_column_index_policy(page_index_policy(policy.column_index()))
+            .with_offset_index_policy(page_index_policy(policy.offset_index()))
+    }};
+}
+
+/// The footer tail and metadata of a file, as pushed to a decoder
+struct Footer {
+    file_len: u64,
+    footer_range: Range<u64>,
+    footer_bytes: Bytes,
+    metadata_range: Range<u64>,
+    metadata_bytes: Bytes,
+}
+
+/// Macro that times decoding `$footer` (and the page indexes of `$benchmark`)
+/// with the `ParquetMetaDataPushDecoder` of the `$parquet` crate, loading the
+/// page indexes selected by `$policy`
+macro_rules! time_decoder {
+    ($parquet:ident, $benchmark:expr, $footer:expr, $policy:expr) => {{
+        use $parquet::DecodeResult;
+        use $parquet::file::metadata::ParquetMetaDataPushDecoder;
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let footer: &Footer = $footer;
+        let policy: PageIndexPolicy = $policy;
+
+        // parse the metadata and index once, returning the time taken
+        // for each (metadata, index)
+        let run_once = || {
+            let decoder = ParquetMetaDataPushDecoder::try_new(footer.file_len).unwrap();
+            let mut decoder = with_page_index_policy!($parquet, decoder, policy);
+            push_range!(&mut decoder, &footer.footer_range, &footer.footer_bytes);
+            push_range!(&mut decoder, &footer.metadata_range, &footer.metadata_bytes);
+            // this will now parse the metadata
+            let region = TimedRegion::start(Phase::Metadata);
+            let res = decoder.try_decode().unwrap();
+            let metadata_parsing_duration = region.elapsed();
+
+            match res {
+                DecodeResult::NeedsData(_range) => { /* expected, continue */ }
+                DecodeResult::Data(_metadata) => {
+                    // no index requested (or parsing disabled in the no stats version)
+                    return (metadata_parsing_duration, Duration::from_secs(0));
+                }
+                DecodeResult::Finished => {
+                    panic!("Expected data or needs data");
+                }
+            }
+            let region = TimedRegion::start(Phase::Index);
+            // the decoder may only need part of the index range
+            push_range!(&mut decoder, &benchmark.index_range, &benchmark.index_bytes);
+            // this is the index parsing
+            let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
+                panic!("Expected to be done with parsing");
+            };
+            let index_parsing_duration = region.elapsed();
+            (metadata_parsing_duration, index_parsing_duration)
+        };
+        benchmark.time(run_once)
+    }};
+}
+
+/// Macro that times `ParquetMetaDataReader` of the `$parquet` crate reading
+/// the metadata and then the page indexes selected by `$policy` from `$tail`.
+///
+/// `$decryption` converts a `&Option<Arc<FileDecryptionProperties>>` to the
+/// type the reader takes (a reference in parquet 56, an `Arc` in parquet 57)
+macro_rules! time_metadata_reader {
+    ($parquet:ident, $benchmark:expr, $tail:expr, $policy:expr, $decryption:path) => {{
+        use $parquet::file::metadata::{
+            PageIndexPolicy as ParquetPageIndexPolicy, ParquetMetaDataReader,
+        };
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let tail: &TailBytes = $tail;
+        let policy = |load| {
+            if load {
+                ParquetPageIndexPolicy::Optional
+            } else {
+                ParquetPageIndexPolicy::Skip
+            }
+        };
+        let column_index_policy = policy($policy.column_index());
+        let offset_index_policy = policy($policy.offset_index());
+        let decryption =
+            decryption_properties!($parquet, benchmark.encryption, &benchmark.column_names);
+
+        let run_once = || {
+            // reads the footer and then the metadata, without page indexes
+            let region = TimedRegion::start(Phase::Metadata);
+            let metadata = ParquetMetaDataReader::new()
+                .with_decryption_properties($decryption(&decryption))
+                .parse_and_finish(tail)
+                .unwrap();
+            let metadata_parsing_duration = region.elapsed();
+            if $policy == PageIndexPolicy::None {
+                return (metadata_parsing_duration, Duration::from_secs(0));
+            }
+
+            let region = TimedRegion::start(Phase::Index);
+            let mut reader = ParquetMetaDataReader::new_with_metadata(metadata)
+                .with_decryption_properties($decryption(&decryption))
+                .with_column_index_policy(column_index_policy)
+                .with_offset_index_policy(offset_index_policy);
+            reader.read_page_indexes(tail).unwrap();
+            let _metadata = reader.finish().unwrap();
+            let index_parsing_duration = region.elapsed();
+            (metadata_parsing_duration, index_parsing_duration)
+        };
+        benchmark.time(run_once)
+    }};
+}
+
+/// Macro that times opening a `SerializedFileReader` of the `$parquet` crate
+/// on `$tail`, with page indexes unless `$policy` is `None`
+macro_rules! time_serialized_file_reader {
+    ($parquet:ident, $benchmark:expr, $tail:expr, $policy:expr) => {{
+        use $parquet::file::serialized_reader::{ReadOptionsBuilder, SerializedFileReader};
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let tail: &TailBytes = $tail;
+
+        // the reader parses the metadata and page indexes in one call, so only the
+        // combined time is measured (see `DecoderConfig::combined_index_time`)
+        let run_once = || {
+            let mut options = ReadOptionsBuilder::new();
+            if $policy != PageIndexPolicy::None {
+                options = options.with_page_index();
+            }
+            let options = options.build();
+            let region = TimedRegion::start(Phase::Metadata);
+            let _reader = SerializedFileReader::new_with_options(tail.clone(), options).unwrap();
+            (region.elapsed(), Duration::from_secs(0))
+        };
+        benchmark.time(run_once)
+    }};
+}
+
+/// Macro that decodes the metadata and page indexes of `$benchmark` with the
+/// `ParquetMetaDataPushDecoder` of the `$parquet` crate, after pushing the
+/// last `$prefetch` bytes of the file, and returns the ranges fetched in each
+/// round trip
+macro_rules! fetched_ranges {
+    ($parquet:ident, $benchmark:expr, $prefetch:expr) => {{
+        use $parquet::DecodeResult;
+        use $parquet::file::metadata::ParquetMetaDataPushDecoder;
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let mut file = fs::File::open(&benchmark.file_path).unwrap();
+        let decoder = ParquetMetaDataPushDecoder::try_new(benchmark.file_len).unwrap();
+        let mut decoder = with_page_index_policy!($parquet, decoder, PageIndexPolicy::Both);
+
+        let mut fetches: Vec<Vec<Range<u64>>> = vec![];
+        if $prefetch > 0 {
+            let prefetch_range = benchmark.file_len.saturating_sub($prefetch)..benchmark.file_len;
+            let bytes = read_byte_range(&mut file, &prefetch_range);
+            push_range!(&mut decoder, &prefetch_range, &bytes);
+            fetches.push(vec![prefetch_range]);
+        }
+        loop {
+            match decoder.try_decode().unwrap() {
+                DecodeResult::NeedsData(ranges) => {
+                    let bytes = ranges
+                        .iter()
+                        .map(|range| read_byte_range(&mut file, range))
+                        .collect();
+                    decoder.push_ranges(ranges.clone(), bytes).unwrap();
+                    fetches.push(ranges);
+                }
+                DecodeResult::Data(_metadata) => break,
+                DecodeResult::Finished => panic!("Expected data or needs data"),
+            }
+        }
+        fetches
+    }};
+}
+
+/// Macro that decodes the metadata and page indexes in `$tail` with the
+/// `$parquet` crate, and times encoding them again with its
+/// `ParquetMetaDataWriter`
+macro_rules! time_metadata_writer {
+    ($parquet:ident, $benchmark:expr, $tail:expr) => {{
+        use $parquet::file::metadata::{
+            PageIndexPolicy as ParquetPageIndexPolicy, ParquetMetaDataReader, ParquetMetaDataWriter,
+        };
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let tail: &TailBytes = $tail;
+        let metadata = ParquetMetaDataReader::new()
+            .with_column_index_policy(ParquetPageIndexPolicy::Optional)
+            .with_offset_index_policy(ParquetPageIndexPolicy::Optional)
+            .parse_and_finish(tail)
+            .unwrap();
+        let capacity = (benchmark.file_len - benchmark.index_range.start) as usize;
+
+        // the page indexes, metadata and footer tail encoded by the last run
+        let buffer = RefCell::new(vec![]);
+        let run_once = || {
+            let mut run_buffer = Vec::with_capacity(capacity);
+            // the time is reported as the metadata phase
+            let region = TimedRegion::start(Phase::Metadata);
+            ParquetMetaDataWriter::new(&mut run_buffer, &metadata)
+                .finish()
+                .unwrap();
+            let duration = region.elapsed();
+            buffer.replace(run_buffer);
+            (duration, Duration::from_secs(0))
+        };
+        let timing = benchmark.time(run_once);
+        MetadataWrite::new(timing, &buffer.into_inner())
+    }};
+}
+
+/// Macro that decodes `$footer` and the page indexes of `$benchmark` with the
+/// `ParquetMetaDataPushDecoder` of the `$parquet` crate, and returns the heap
+/// size of the decoded metadata without and with both page indexes
+macro_rules! decoded_memory_size {
+    ($parquet:ident, $benchmark:expr, $footer:expr) => {{
+        use $parquet::DecodeResult;
+        use $parquet::file::metadata::ParquetMetaDataPushDecoder;
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let footer: &Footer = $footer;
+        let memory_size = |policy: PageIndexPolicy| {
+            let decoder = ParquetMetaDataPushDecoder::try_new(footer.file_len).unwrap();
+            let mut decoder = with_page_index_policy!($parquet, decoder, policy);
+            push_range!(&mut decoder, &footer.footer_range, &footer.footer_bytes);
+            push_range!(&mut decoder, &footer.metadata_range, &footer.metadata_bytes);
+            push_range!(&mut decoder, &benchmark.index_range, &benchmark.index_bytes);
+            let DecodeResult::Data(metadata) = decoder.try_decode().unwrap() else {
+                panic!("Expected to be done with parsing");
+            };
+            metadata.memory_size()
+        };
+        MemorySize {
+            metadata: memory_size(PageIndexPolicy::None),
+            with_page_indexes: memory_size(PageIndexPolicy::Both),
+        }
+    }};
+}
+
+/// Macro that decodes the metadata and page indexes of the encrypted file of
+/// `$benchmark` from `$tail` with `ParquetMetaDataReader` of the `$parquet`
+/// crate and the decryption properties, and returns the heap size of the
+/// decoded metadata without and with both page indexes. `$decryption` is as in
+/// `time_metadata_reader!`
+macro_rules! decrypted_memory_size {
+    ($parquet:ident, $benchmark:expr, $tail:expr, $decryption:path) => {{
+        use $parquet::file::metadata::{
+            PageIndexPolicy as ParquetPageIndexPolicy, ParquetMetaDataReader,
+        };
+        let benchmark: &MetadataParseBenchmark = $benchmark;
+        let tail: &TailBytes = $tail;
+        let decryption =
+            decryption_properties!($parquet, benchmark.encryption, &benchmark.column_names);
+        let metadata = ParquetMetaDataReader::new()
+            .with_decryption_properties($decryption(&decryption))
+            .parse_and_finish(tail)
+            .unwrap();
+        let metadata_size = metadata.memory_size();
+        let mut reader = ParquetMetaDataReader::new_with_metadata(metadata)
+            .with_decryption_properties($decryption(&decryption))
+            .with_column_index_policy(ParquetPageIndexPolicy::Optional)
+            .with_offset_index_policy(ParquetPageIndexPolicy::Optional);
+        reader.read_page_indexes(tail).unwrap();
+        MemorySize {
+            metadata: metadata_size,
+            with_page_indexes: reader.finish().unwrap().memory_size(),
+        }
+    }};
+}
+
+/// Returns the bytes fetched in all of `fetches`, and how many of them were
+/// over-read: fetched more than once, or before `tail_start` (the start of the
+/// page indexes and metadata)
+fn fetched_bytes(fetches: &[Vec<Range<u64>>], tail_start: u64) -> (u64, u64) {
+    let mut ranges: Vec<&Range<u64>> = fetches.iter().flatten().collect();
+    let bytes_fetched = ranges.iter().map(|range| range.end - range.start).sum();
+
+    // bytes of the tail fetched at least once
+    ranges.sort_by_key(|range| range.start);
+    let mut useful = 0;
+    let mut covered_to = tail_start;
+    for range in ranges {
+        let start = range.start.max(covered_to);
+        if range.end > start {
+            useful += range.end - start;
+            covered_to = range.end;
+        }
+    }
+    (bytes_fetched, bytes_fetched - useful)
+}
+
+/// Returns a mask that selects `percent`% of `columns` (rounded to the nearest
+/// column), spread evenly across the schema
+fn projection_mask(columns: usize, percent: usize) -> Vec<bool> {
+    let selected = (columns * percent.min(100) + 50) / 100;
+    (0..columns)
+        .map(|column| (column + 1) * selected / columns > column * selected / columns)
+        .collect()
+}
+
+/// Returns the (footer, metadata, page index) ranges of a file, as requested by
+/// the parquet 56 push decoder
+fn locate_footer(file: &mut fs::File, file_len: u64) -> (Range<u64>, Range<u64>, Range<u64>) {
+    use parquet_56::DecodeResult;
+    use parquet_56::file::metadata::ParquetMetaDataPushDecoder;
+
+    let mut decoder = ParquetMetaDataPushDecoder::try_new(file_len).unwrap();
+
+    // decode the metadata once to get the locations
+    let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
+        panic!("Needs expected footer bytes");
+    };
+    assert_eq!(ranges.len(), 1);
+    let footer_range = ranges.pop().unwrap();
+    let footer_bytes = read_byte_range(file, &footer_range);
+    push_range!(&mut decoder, &footer_range, &footer_bytes);
+
+    let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
+        panic!("Needs expected metadata bytes");
+    };
+    assert_eq!(ranges.len(), 1);
+    let metadata_range = ranges.pop().unwrap();
+    let metadata_bytes = read_byte_range(file, &metadata_range);
+    push_range!(&mut decoder, &metadata_range, &metadata_bytes);
+
+    // now read index range
+    let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
+        panic!("Needs expected index bytes");
+    };
+    // should be one index range
+    assert_eq!(ranges.len(), 1);
+    let index_range = ranges.pop().unwrap();
+    let index_bytes = read_byte_range(file, &index_range);
+    push_range!(&mut decoder, &index_range, &index_bytes);
+
+    let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
+        panic!("Expected to be done with parsing");
+    };
+    (footer_range, metadata_range, index_range)
+}
+
+/// Returns the (footer, metadata, page index) ranges of an encrypted file. The
+/// push decoder can not decrypt, so the metadata is decoded with the parquet 56
+/// `ParquetMetaDataReader` and the page index range is the span of the page
+/// index offsets of every column chunk
+fn locate_encrypted_footer(
+    file: &fs::File,
+    file_len: u64,
+    encryption: Encryption,
+    column_names: &[String],
+) -> (Range<u64>, Range<u64>, Range<u64>) {
+    use parquet_56::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+    use parquet_56::file::reader::ChunkReader;
+
+    let footer_range = file_len - 8..file_len;
+    let footer_bytes = file.get_bytes(footer_range.start, 8).unwrap();
+    let footer_tail =
+        ParquetMetaDataReader::decode_footer_tail(&footer_bytes[..].try_into().unwrap()).unwrap();
+    let metadata_range =
+        footer_range.start - footer_tail.metadata_length() as u64..footer_range.start;
+
+    let decryption = decryption_properties!(parquet_56, encryption, column_names);
+    let metadata = ParquetMetaDataReader::new()
+        .with_page_index_policy(PageIndexPolicy::Skip)
+        .with_decryption_properties(decryption.as_deref())
+        .parse_and_finish(file)
+        .unwrap();
+    let index_ranges = metadata
+        .row_groups()
+        .iter()
+        .flat_map(|row_group| row_group.columns())
+        .flat_map(|column| {
+            let range = |offset: Option<i64>, length: Option<i32>| {
+                Some(offset? as u64..offset? as u64 + length? as u64)
+            };
+            [
+                range(column.column_index_offset(), column.column_index_length()),
+                range(column.offset_index_offset(), column.offset_index_length()),
+            ]
+        })
+        .flatten();
+    let index_range = index_ranges
+        .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+        .expect("encrypted files are written with page indexes");
+    (footer_range, metadata_range, index_range)
+}
 
 // reads the specified byte range from the cursor into a contiguous memory region
 // (Bytes object)
@@ -58,53 +729,40 @@ fn read_byte_range(cursor: &mut (impl Read + Seek), range: &Range<u64>) -> Bytes
     Bytes::from(buffer)
 }
 
-
 impl MetadataParseBenchmark {
-    pub fn new(description: impl Into<String>, file_path: PathBuf) -> Self {
-        use parquet_56::file::metadata::ParquetMetaDataPushDecoder;
-        use parquet_56::DecodeResult;
+    pub fn new(spec: &ParquetFileSpec) -> Self {
+        let file_path = spec.path().clone();
 
         let mut file = fs::File::open(&file_path).unwrap();
         let file_len = fs::metadata(&file_path).unwrap().len();
-        let mut decoder = ParquetMetaDataPushDecoder::try_new(file_len).unwrap();
-
-        // decode the metadata once to get the locations
-        let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
-            panic!("Needs expected footer bytes");
+        let encryption = spec.encryption();
+        let column_names = if encryption == Encryption::None {
+            vec![]
+        } else {
+            spec.column_names()
         };
-        assert_eq!(ranges.len(), 1);
-        let footer_range = ranges.pop().unwrap();
-        let footer_bytes = read_byte_range(&mut file, &footer_range);
-        push_range!(&mut decoder, &footer_range, &footer_bytes);
-
-        let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
-            panic!("Needs expected metadata bytes");
+        let (footer_range, metadata_range, index_range) = if encryption == Encryption::None {
+            locate_footer(&mut file, file_len)
+        } else {
+            locate_encrypted_footer(&file, file_len, encryption, &column_names)
         };
-        assert_eq!(ranges.len(), 1);
-        let metadata_range = ranges.pop().unwrap();
+        let footer_bytes = read_byte_range(&mut file, &footer_range);
         let metadata_bytes = read_byte_range(&mut file, &metadata_range);
-        push_range!(&mut decoder, &metadata_range, &metadata_bytes);
-
-        // now read index range
-        let DecodeResult::NeedsData(mut ranges) = decoder.try_decode().unwrap() else {
-            panic!("Needs expected index bytes");
-        };
-        // should be one index range
-        assert_eq!(ranges.len(), 1);
-        let index_range = ranges.pop().unwrap();
         let index_bytes = read_byte_range(&mut file, &index_range);
-        push_range!(&mut decoder, &index_range, &index_bytes);
-
-        let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
-            panic!("Expected to be done with parsing");
-        };
 
-        // TODO: make num_runs configurable
-        let num_runs = 10;
-        //let num_runs = 100;
         Self {
-            description: description.into(),
-            num_runs,
+            description: spec.to_string(),
+            writer_version: spec.writer_version(),
+            writer_crate: spec.writer_crate(),
+            columns: spec.columns(),
+            row_groups: spec.row_groups(),
+            seed: spec.seed(),
+            encryption,
+            column_names,
+            num_runs: 10,
+            warmup_runs: 10,
+            prefetch_hints: vec![],
+            metadata_writers: vec![],
             file_path,
             file_len,
             footer_range,
@@ -116,86 +774,52 @@ impl MetadataParseBenchmark {
         }
     }
 
-    /// Released arrow 56 uses the thrift compiler to generate the parquet metadata structs
-    fn run_arrow_56(&self) -> Timing {
-        use parquet_56::file::metadata::ParquetMetaDataPushDecoder;
-        use parquet_56::DecodeResult;
-        println!("Arrow 56 (using thrift compiler)...");
-
-        // parse the metadata and index once, returning the time taken
-        // for each (metadata, index)
-        let run_once = || {
-            let mut decoder = ParquetMetaDataPushDecoder::try_new(self.file_len).unwrap();
-            push_range!(&mut decoder, &self.footer_range, &self.footer_bytes);
-            push_range!(&mut decoder, &self.metadata_range, &self.metadata_bytes);
-            // this will now parse the metadata
-            let start = std::time::Instant::now();
-            let DecodeResult::NeedsData(_range) = decoder.try_decode().unwrap() else {
-                panic!("Needs expected index bytes");
-            };
-            let metadata_parsing_duration = start.elapsed();
-            let start = std::time::Instant::now();
-            push_range!(&mut decoder, &self.index_range, &self.index_bytes);
-            // this is the index parsing
-            let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
-                panic!("Expected to be done with parsing");
-            };
-            let index_parsing_duration = start.elapsed();
-            (metadata_parsing_duration, index_parsing_duration)
-        } ;
+    /// Sets the number of timed runs for each decoder
+    pub fn with_num_runs(mut self, num_runs: usize) -> Self {
+        self.num_runs = num_runs;
+        self
+    }
 
-        // warm up with 10 runs
-        for _ in 0..10 {
-            run_once();
-        }
+    /// Sets the number of untimed warm up runs for each decoder
+    pub fn with_warmup_runs(mut self, warmup_runs: usize) -> Self {
+        self.warmup_runs = warmup_runs;
+        self
+    }
 
-        // now run the actual benchmark
-        let mut metadata_parsing_duration = Duration::from_secs(0);
-        let mut index_parsing_duration = Duration::from_secs(0);
-        for _ in 0..self.num_runs {
-            let (md_duration, idx_duration) = run_once();
-            metadata_parsing_duration += md_duration;
-            index_parsing_duration += idx_duration;
-        }
-        Timing {
-            num_runs: self.num_runs,
-            metadata_parsing_duration,
-            index_parsing_duration,
-        }
+    /// Sets the prefetch sizes for which the round trips of each push decoder
+    /// are counted
+    pub fn with_prefetch_hints(mut self, prefetch_hints: Vec<u64>) -> Self {
+        self.prefetch_hints = prefetch_hints;
+        self
     }
 
-    /// Arrow 57 uses a custom thrift decoder for parquet metadata
-    fn run_arrow_57(&self) -> Timing {
-        use parquet_57::file::metadata::ParquetMetaDataPushDecoder;
-        use parquet_57::DecodeResult;
-        println!("Arrow 57 (custom thrift decoder)...");
+    /// Sets the parquet crates whose `ParquetMetaDataWriter` is timed encoding
+    /// the decoded metadata and page indexes of the file
+    pub fn with_metadata_writers(mut self, metadata_writers: Vec<WriterCrate>) -> Self {
+        self.metadata_writers = metadata_writers;
+        self
+    }
 
-        // parse the metadata and index once, returning the time taken
-        // for each (metadata, index)
-        let run_once = || {
-            let mut decoder = ParquetMetaDataPushDecoder::try_new(self.file_len).unwrap();
-            push_range!(&mut decoder, &self.footer_range, &self.footer_bytes);
-            push_range!(&mut decoder, &self.metadata_range, &self.metadata_bytes);
-            // this will now parse the metadata
-            let start = std::time::Instant::now();
-            let DecodeResult::NeedsData(_range) = decoder.try_decode().unwrap() else {
-                panic!("Needs expected index bytes");
-            };
-            let metadata_parsing_duration = start.elapsed();
-            let start = std::time::Instant::now();
-            push_range!(&mut decoder, &self.index_range, &self.index_bytes);
-            // this is the index parsing
-            let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
-                panic!("Expected to be done with parsing");
-            };
-            let index_parsing_duration = start.elapsed();
-            (metadata_parsing_duration, index_parsing_duration)
-        } ;
+    /// The footer and metadata bytes of the file, as pushed to the decoders
+    fn footer(&self) -> Footer {
+        Footer {
+            file_len: self.file_len,
+            footer_range: self.footer_range.clone(),
+            footer_bytes: self.footer_bytes.clone(),
+            metadata_range: self.metadata_range.clone(),
+            metadata_bytes: self.metadata_bytes.clone(),
+        }
+    }
 
-        // warm up with 10 runs
-        for _ in 0..10 {
+    /// Runs `run_once` for the warm up runs and then the timed runs, returning
+    /// the total (metadata, index) parse times of the timed runs
+    fn time(&self, run_once: impl Fn() -> (Duration, Duration)) -> Timing {
+        for _ in 0..self.warmup_runs {
             run_once();
         }
+        // only count the allocations and hardware counters of the timed runs
+        allocations::take();
+        perf_counters::take();
 
         // now run the actual benchmark
         let mut metadata_parsing_duration = Duration::from_secs(0);
@@ -209,79 +833,312 @@ impl MetadataParseBenchmark {
             num_runs: self.num_runs,
             metadata_parsing_duration,
             index_parsing_duration,
+            allocations: allocations::ENABLED.then(allocations::take),
+            perf: perf_counters::take(),
         }
     }
 
+    /// Released arrow 56 uses the thrift compiler to generate the parquet metadata structs
+    fn run_arrow_56(&self, policy: PageIndexPolicy) -> Timing {
+        println!("Arrow 56 (using thrift compiler, {policy})...");
+        time_decoder!(parquet_56, self, &self.footer(), policy)
+    }
+
+    /// Arrow 57 uses a custom thrift decoder for parquet metadata
+    fn run_arrow_57(&self, policy: PageIndexPolicy) -> Timing {
+        println!("Arrow 57 (custom thrift decoder, {policy})...");
+        time_decoder!(parquet_57, self, &self.footer(), policy)
+    }
 
     /// Hacked version of Arrow 57 that skips all statistics
-    fn run_arrow_57_no_stats(&self) -> Timing {
-        use parquet_57_no_stats::file::metadata::ParquetMetaDataPushDecoder;
-        use parquet_57_no_stats::DecodeResult;
-        println!("Arrow 57 (custom thrift decoder, no stats)...");
+    fn run_arrow_57_no_stats(&self, policy: PageIndexPolicy) -> Timing {
+        println!("Arrow 57 (custom thrift decoder, no stats, {policy})...");
+        time_decoder!(parquet_57_no_stats, self, &self.footer(), policy)
+    }
 
-        // parse the metadata and index once, returning the time taken
-        // for each (metadata, index)
-        let run_once = || {
-            let mut decoder = ParquetMetaDataPushDecoder::try_new(self.file_len).unwrap();
-            push_range!(&mut decoder, &self.footer_range, &self.footer_bytes);
-            push_range!(&mut decoder, &self.metadata_range, &self.metadata_bytes);
-            // this will now parse the metadata
-            let start = std::time::Instant::now();
-            let res = decoder.try_decode().unwrap();
-            let metadata_parsing_duration = start.elapsed();
+    /// Released Arrow 57 decoding a re-encoded footer with the statistics and
+    /// column indexes of all but `percent`% of the columns removed.
+    ///
+    /// Compared to the footer re-encoded with all statistics (`percent` 100),
+    /// this approximates the work a projection-aware decoder would save for a
+    /// query that only needs statistics for those columns
+    fn run_arrow_57_projected(&self, percent: usize, policy: PageIndexPolicy) -> Timing {
+        println!(
+            "Arrow 57 (custom thrift decoder, re-encoded footer with statistics for {percent}% \
+             of columns, {policy})..."
+        );
+        time_decoder!(parquet_57, self, &self.projected_footer(percent), policy)
+    }
 
-            match res {
-                DecodeResult::NeedsData(_range) => { /* expected, continue */ }
-                DecodeResult::Data(_metadata) => {
-                    // no index present (b/c we disabled parsing those fields)
-                    return (metadata_parsing_duration, Duration::from_secs(0));
-                }
-                DecodeResult::Finished => {
-                    panic!("Expected data or needs data");
-                }
-            }
-            let start = std::time::Instant::now();
-            push_range!(&mut decoder, &self.index_range, &self.index_bytes);
-            // this is the index parsing
-            let DecodeResult::Data(_metadata) = decoder.try_decode().unwrap() else {
-                panic!("Expected to be done with parsing");
+    /// The "no stats" fork decoding the footer re-encoded with all statistics, so
+    /// the projected footers can be compared to it on the same bytes
+    fn run_arrow_57_no_stats_reencoded(&self, policy: PageIndexPolicy) -> Timing {
+        println!("Arrow 57 (custom thrift decoder, no stats, re-encoded footer, {policy})...");
+        time_decoder!(
+            parquet_57_no_stats,
+            self,
+            &self.projected_footer(100),
+            policy
+        )
+    }
+
+    /// The footer re-encoded by the parquet 57 writer, with the statistics and
+    /// column indexes of all but `percent`% of the columns removed
+    fn projected_footer(&self, percent: usize) -> Footer {
+        let projection = projection_mask(self.columns, percent);
+        // the rewritten footer starts where the original one did, so the page index
+        // ranges are unchanged (the offset index of every column is kept)
+        let footer = footer::v57::reencode_footer(&self.metadata_bytes, |metadata| {
+            footer::v57::clear_unprojected_statistics(metadata, &projection)
+        });
+        let footer_size = self.footer_range.end - self.footer_range.start;
+        let file_len = self.metadata_range.start + footer.len() as u64;
+        let metadata_range = self.metadata_range.start..file_len - footer_size;
+        let footer = Bytes::from(footer);
+        let metadata_bytes = footer.slice(..(metadata_range.end - metadata_range.start) as usize);
+        Footer {
+            file_len,
+            footer_range: file_len - footer_size..file_len,
+            footer_bytes: footer.slice(metadata_bytes.len()..),
+            metadata_range,
+            metadata_bytes,
+        }
+    }
+
+    /// Heap size of the metadata decoded by `decoder`, or `None` if it can not
+    /// read the encrypted file. The readers decode the same `ParquetMetaData`
+    /// as the push decoder of their crate, which can not decrypt, so encrypted
+    /// files are measured with `ParquetMetaDataReader`
+    fn decoded_memory_size(&self, decoder: Decoder) -> Option<MemorySize> {
+        if self.encryption != Encryption::None {
+            let tail = self.tail();
+            return match decoder {
+                Decoder::Arrow56MetadataReader => Some(decrypted_memory_size!(
+                    parquet_56,
+                    self,
+                    &tail,
+                    Option::as_deref
+                )),
+                Decoder::Arrow57MetadataReader => Some(decrypted_memory_size!(
+                    parquet_57,
+                    self,
+                    &tail,
+                    Option::clone
+                )),
+                _ => None,
             };
-            let index_parsing_duration = start.elapsed();
-            (metadata_parsing_duration, index_parsing_duration)
-        } ;
+        }
+        let footer = self.footer();
+        let memory_size = match decoder {
+            Decoder::Arrow56
+            | Decoder::Arrow56MetadataReader
+            | Decoder::Arrow56SerializedFileReader => {
+                decoded_memory_size!(parquet_56, self, &footer)
+            }
+            Decoder::Arrow57
+            | Decoder::Arrow57MetadataReader
+            | Decoder::Arrow57SerializedFileReader => {
+                decoded_memory_size!(parquet_57, self, &footer)
+            }
+            Decoder::Arrow57NoStats => decoded_memory_size!(parquet_57_no_stats, self, &footer),
+            Decoder::Arrow57Projected(percent) => {
+                decoded_memory_size!(parquet_57, self, &self.projected_footer(percent))
+            }
+            Decoder::Arrow57NoStatsReencoded => {
+                decoded_memory_size!(parquet_57_no_stats, self, &self.projected_footer(100))
+            }
+        };
+        Some(memory_size)
+    }
 
+    /// The page indexes, metadata and footer of the file, for the synchronous
+    /// readers. Read before timing, like the bytes pushed to the decoders
+    fn tail(&self) -> TailBytes {
+        let start = self.index_range.start.min(self.metadata_range.start);
+        let mut file = fs::File::open(&self.file_path).unwrap();
+        TailBytes::new(
+            self.file_len,
+            read_byte_range(&mut file, &(start..self.file_len)),
+        )
+    }
 
-        // warm up with 10 runs
-        for _ in 0..10 {
-            run_once();
+    /// `ParquetMetaDataReader`, which most code uses to read metadata from a
+    /// `File` or `Bytes`
+    fn run_metadata_reader(&self, decoder: Decoder, policy: PageIndexPolicy) -> Timing {
+        println!("{decoder} ({policy})...");
+        let tail = self.tail();
+        match decoder {
+            Decoder::Arrow56MetadataReader => {
+                time_metadata_reader!(parquet_56, self, &tail, policy, Option::as_deref)
+            }
+            Decoder::Arrow57MetadataReader => {
+                time_metadata_reader!(parquet_57, self, &tail, policy, Option::clone)
+            }
+            _ => panic!("{decoder} is not a ParquetMetaDataReader"),
         }
+    }
 
-        // now run the actual benchmark
-        let mut metadata_parsing_duration = Duration::from_secs(0);
-        let mut index_parsing_duration = Duration::from_secs(0);
-        for _ in 0..self.num_runs {
-            let (md_duration, idx_duration) = run_once();
-            metadata_parsing_duration += md_duration;
-            index_parsing_duration += idx_duration;
+    /// `SerializedFileReader`, the synchronous (non arrow) file reader
+    fn run_serialized_file_reader(&self, decoder: Decoder, policy: PageIndexPolicy) -> Timing {
+        println!("{decoder} ({policy})...");
+        let tail = self.tail();
+        match decoder {
+            Decoder::Arrow56SerializedFileReader => {
+                time_serialized_file_reader!(parquet_56, self, &tail, policy)
+            }
+            Decoder::Arrow57SerializedFileReader => {
+                time_serialized_file_reader!(parquet_57, self, &tail, policy)
+            }
+            _ => panic!("{decoder} is not a SerializedFileReader"),
         }
-        Timing {
-            num_runs: self.num_runs,
-            metadata_parsing_duration,
-            index_parsing_duration,
+    }
+
+    /// Runs `config`, or returns `None` if the decoder can not read this file
+    fn run_decoder(&self, config: DecoderConfig) -> Option<Timing> {
+        if self.encryption != Encryption::None && !config.decoder.reads_encrypted() {
+            println!("{config} can not read encrypted files, skipping");
+            return None;
+        }
+        let policy = config.page_index_policy;
+        let timing = match config.decoder {
+            Decoder::Arrow56 => self.run_arrow_56(policy),
+            Decoder::Arrow57 => self.run_arrow_57(policy),
+            Decoder::Arrow57NoStats => self.run_arrow_57_no_stats(policy),
+            Decoder::Arrow57Projected(percent) => self.run_arrow_57_projected(percent, policy),
+            Decoder::Arrow57NoStatsReencoded => self.run_arrow_57_no_stats_reencoded(policy),
+            decoder @ (Decoder::Arrow56MetadataReader | Decoder::Arrow57MetadataReader) => {
+                self.run_metadata_reader(decoder, policy)
+            }
+            decoder @ (Decoder::Arrow56SerializedFileReader
+            | Decoder::Arrow57SerializedFileReader) => {
+                self.run_serialized_file_reader(decoder, policy)
+            }
+        };
+        Some(timing)
+    }
+
+    /// Counts the round trips and over-read bytes of `decoder` when the reader
+    /// first fetches the last `prefetch` bytes of the file. Over-read bytes are
+    /// fetched bytes that are not part of the page indexes, metadata or footer,
+    /// or that were already fetched
+    fn count_round_trips(&self, decoder: Decoder, prefetch: u64) -> RoundTrips {
+        let fetches = match decoder {
+            Decoder::Arrow56 => fetched_ranges!(parquet_56, self, prefetch),
+            Decoder::Arrow57 => fetched_ranges!(parquet_57, self, prefetch),
+            Decoder::Arrow57NoStats => fetched_ranges!(parquet_57_no_stats, self, prefetch),
+            _ => panic!("{decoder} is not a push decoder"),
+        };
+        let tail_start = self.index_range.start.min(self.metadata_range.start);
+        let (bytes_fetched, over_read) = fetched_bytes(&fetches, tail_start);
+        RoundTrips {
+            prefetch,
+            round_trips: fetches.len(),
+            bytes_fetched,
+            over_read,
         }
     }
 
+    /// Times the metadata writer of `writer_crate`, or returns `None` for
+    /// encrypted files (the writer does not encrypt the metadata)
+    fn run_metadata_writer(&self, writer_crate: WriterCrate) -> Option<MetadataWrite> {
+        if self.encryption != Encryption::None {
+            println!("Not timing the {writer_crate} metadata writer on an encrypted file");
+            return None;
+        }
+        println!("Writing metadata with {writer_crate}...");
+        let tail = self.tail();
+        let metadata_write = match writer_crate {
+            WriterCrate::Parquet56 => time_metadata_writer!(parquet_56, self, &tail),
+            WriterCrate::Parquet57 => time_metadata_writer!(parquet_57, self, &tail),
+        };
+        Some(metadata_write)
+    }
 
-    pub fn run(&self) -> MetadataParseResult {
+    /// Runs the benchmark for each of `configs`, and counts the round trips of
+    /// each push decoder in `configs` for each prefetch hint
+    pub fn run(&self, configs: &[DecoderConfig]) -> MetadataParseResult {
         print!("Running metadata parse benchmark on {self:#?} ... ");
 
+        let decoders = distinct_decoders(configs);
+        let round_trips = decoders
+            .iter()
+            .copied()
+            .filter(|decoder| {
+                decoder.counts_round_trips()
+                    && (self.encryption == Encryption::None || decoder.reads_encrypted())
+            })
+            .flat_map(|decoder| {
+                self.prefetch_hints
+                    .iter()
+                    .map(move |prefetch| (decoder, self.count_round_trips(decoder, *prefetch)))
+            })
+            .collect();
+
         MetadataParseResult {
             description: self.description.clone(),
-            arrow_56_timing: self.run_arrow_56(),
-            arrow_57_timing: self.run_arrow_57(),
-            arrow_57_timing_no_stats: self.run_arrow_57_no_stats(),
+            writer_version: self.writer_version,
+            writer_crate: self.writer_crate,
+            columns: self.columns,
+            row_groups: self.row_groups,
+            seed: self.seed,
+            encryption: self.encryption,
+            metadata_size: self.metadata_bytes.len(),
+            index_size: self.index_bytes.len(),
+            timings: configs
+                .iter()
+                .map(|config| (*config, self.run_decoder(*config)))
+                .collect(),
+            round_trips,
+            memory_sizes: decoders
+                .iter()
+                .map(|decoder| (*decoder, self.decoded_memory_size(*decoder)))
+                .collect(),
+            metadata_writes: self
+                .metadata_writers
+                .iter()
+                .map(|writer_crate| (*writer_crate, self.run_metadata_writer(*writer_crate)))
+                .collect(),
+        }
+    }
+}
+
+/// Heap size of decoded metadata, in bytes
+#[derive(Debug, Clone, Copy)]
+pub struct MemorySize {
+    /// `ParquetMetaData::memory_size` without page indexes
+    metadata: usize,
+    /// `ParquetMetaData::memory_size` with the column and offset indexes
+    with_page_indexes: usize,
+}
+
+/// Time to encode the decoded metadata and page indexes of a file, and the
+/// size of the result
+#[derive(Debug)]
+pub struct MetadataWrite {
+    /// the timed runs, with the write time as the metadata parse time
+    timing: Timing,
+    /// size of the encoded footer metadata in bytes
+    metadata_size: usize,
+    /// size of the encoded page indexes in bytes
+    index_size: usize,
+}
+
+impl MetadataWrite {
+    /// `buffer` is the page indexes, metadata and footer tail written by the
+    /// last run
+    fn new(timing: Timing, buffer: &[u8]) -> Self {
+        let tail = &buffer[buffer.len() - 8..];
+        let metadata_size = u32::from_le_bytes(tail[..4].try_into().unwrap()) as usize;
+        Self {
+            timing,
+            metadata_size,
+            index_size: buffer.len() - 8 - metadata_size,
         }
     }
+
+    pub fn avg_duration(&self) -> Duration {
+        self.timing.avg_metadata_parsing_duration()
+    }
 }
 
 #[derive(Debug)]
@@ -292,6 +1149,12 @@ pub struct Timing {
     metadata_parsing_duration: Duration,
     /// Total duration for parsing the page indexes (page index and column index)
     index_parsing_duration: Duration,
+    /// Total allocations in the timed regions (`None` without the
+    /// `count-allocations` feature)
+    allocations: Option<AllocationCounts>,
+    /// Total hardware counters of the timed regions (`None` without the
+    /// `perf-counters` feature, or if the counters could not be opened)
+    perf: Option<PhaseCounts>,
 }
 
 impl Display for Timing {
@@ -307,80 +1170,775 @@ impl Display for Timing {
             "    PageIndex (Column/Offset) {:?}",
             self.index_parsing_duration / self.num_runs as u32
         )?;
+        if let Some(allocations) = self.avg_allocations() {
+            writeln!(
+                f,
+                "    Allocations {}, deallocations {}, {} bytes allocated",
+                allocations.allocations, allocations.deallocations, allocations.bytes
+            )?;
+        }
+        if let Some(perf) = self.avg_perf() {
+            for (phase, counts) in [("Metadata", perf.metadata), ("PageIndex", perf.index)] {
+                writeln!(
+                    f,
+                    "    {phase} instructions {}, cycles {}, branch misses {}, L1D misses {}, LLC misses {}",
+                    counts.instructions,
+                    counts.cycles,
+                    counts.branch_misses,
+                    counts.l1d_misses,
+                    counts.llc_misses
+                )?;
+            }
+        }
         Ok(())
     }
 }
 
 impl Timing {
     pub fn avg_metadata_parsing_duration(&self) -> Duration {
-        self.metadata_parsing_duration  / self.num_runs as u32
+        self.metadata_parsing_duration / self.num_runs as u32
     }
     pub fn avg_index_parsing_duration(&self) -> Duration {
-        self.index_parsing_duration  / self.num_runs as u32
+        self.index_parsing_duration / self.num_runs as u32
+    }
+    /// Average allocations per run, if allocations are counted
+    pub fn avg_allocations(&self) -> Option<AllocationCounts> {
+        self.allocations
+            .map(|allocations| allocations.per_run(self.num_runs))
+    }
+    /// Average hardware counters per run, if the counters are read
+    pub fn avg_perf(&self) -> Option<PhaseCounts> {
+        self.perf.map(|perf| perf.per_run(self.num_runs))
     }
 }
 
 #[derive(Debug)]
 pub struct MetadataParseResult {
     description: String,
-    /// Timing for arrow-rs 56 (using thrift compiler)
-    arrow_56_timing: Timing,
-    /// Timing for arrow-rs 57 (using custom thrift parser)
-    arrow_57_timing: Timing,
-    /// Timing for arrow-rs 57 (using custom thrift parser), skip all statistics
-    arrow_57_timing_no_stats: Timing,
+    /// writer version used to write the file
+    writer_version: WriterVersion,
+    /// parquet crate used to write the file
+    writer_crate: WriterCrate,
+    /// number of columns in the file
+    columns: usize,
+    /// number of row groups in the file
+    row_groups: usize,
+    /// seed of the generated data
+    seed: u64,
+    /// parquet modular encryption of the file
+    encryption: Encryption,
+    /// size of the footer metadata in bytes
+    metadata_size: usize,
+    /// size of the page indexes (column and offset) in bytes
+    index_size: usize,
+    /// Timing for each decoder, in the order they were run (`None` if the
+    /// decoder can not read the file)
+    timings: Vec<(DecoderConfig, Option<Timing>)>,
+    /// I/O of each push decoder for each prefetch hint
+    round_trips: Vec<(Decoder, RoundTrips)>,
+    /// Heap size of the metadata decoded by each decoder (`None` if the decoder
+    /// can not read the file)
+    memory_sizes: Vec<(Decoder, Option<MemorySize>)>,
+    /// Metadata writer timing and sizes of each parquet crate (`None` for
+    /// encrypted files)
+    metadata_writes: Vec<(WriterCrate, Option<MetadataWrite>)>,
+}
 
+/// Reads needed to load the metadata and page indexes with a prefetch hint
+#[derive(Debug, Clone, Copy)]
+pub struct RoundTrips {
+    /// bytes read from the end of the file before decoding
+    prefetch: u64,
+    /// reads, including the prefetch
+    round_trips: usize,
+    bytes_fetched: u64,
+    /// fetched bytes that were not needed
+    over_read: u64,
+}
+
+/// Table headers: `headers` followed by the metadata and page index parse times
+/// of each decoder configuration
+fn with_timing_headers(headers: &[&str], configs: &[DecoderConfig]) -> Vec<String> {
+    let mut headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+    for config in configs {
+        let metadata_label = config.metadata_label();
+        let index_label = config.index_label();
+        headers.push(format!("Parse Time {config}\n\n{metadata_label}"));
+        headers.push(format!("Parse Time {config}\n\n{index_label}"));
+    }
+    headers
 }
 
 impl MetadataParseResult {
-    pub fn set_headers(table: &mut Table) {
-        table.set_header(vec![
-            "Description",
-            "Parse Time Arrow 56\n\nMetadata",
-            "Parse Time Arrow 56\n\nPageIndex (Column/Offset)",
-            "Parse Time Arrow 57\n\nMetadata",
-            "Parse Time Arrow 57\n\nPageIndex (Column/Offset)",
-            "Parse Time Arrow 57 (no stats)\n\nMetadata",
-            "Parse Time Arrow 57 (no stats)\n\nPageIndex (Column/Offset)",
-        ]);
+    pub fn set_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        let mut headers = with_timing_headers(
+            &[
+                "Description",
+                "Writer Version",
+                "Writer Crate",
+                "Metadata Size",
+                "Index Size",
+            ],
+            configs,
+        );
+        if allocations::ENABLED {
+            for config in configs {
+                headers.push(format!("Allocations {config}\n\nper run"));
+            }
+        }
+        table.set_header(headers);
     }
 
-    pub fn csv_headers() -> Vec<&'static str> {
-        vec![
+    pub fn csv_headers(configs: &[DecoderConfig]) -> Vec<String> {
+        let mut headers: Vec<String> = [
             "Description",
-            "Parse Time Arrow 56 Metadata (ns)",
-            "Parse Time Arrow 56 PageIndex (Column/Offset) (ns)",
-            "Parse Time Arrow 57 Metadata (ns)",
-            "Parse Time Arrow 57 PageIndex (Column/Offset) (ns)",
-            "Parse Time Arrow 57 (no stats) Metadata (ns)",
-            "Parse Time Arrow 57 (no stats) PageIndex (Column/Offset) (ns)",
+            "Writer Version",
+            "Writer Crate",
+            "Columns",
+            "Row Groups",
+            "Seed",
+            "Metadata Size (bytes)",
+            "Index Size (bytes)",
         ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        for config in configs {
+            let metadata_label = config.metadata_label();
+            let index_label = config.index_label();
+            headers.push(format!("Parse Time {config} {metadata_label} (ns)"));
+            headers.push(format!("Parse Time {config} {index_label} (ns)"));
+        }
+        if allocations::ENABLED {
+            for config in configs {
+                headers.push(format!("Allocations {config}"));
+                headers.push(format!("Deallocations {config}"));
+                headers.push(format!("Allocated {config} (bytes)"));
+            }
+        }
+        if perf_counters::ENABLED {
+            for config in configs {
+                for phase in [config.metadata_label(), config.index_label()] {
+                    for counter in PERF_COUNTERS {
+                        headers.push(format!("{counter} {config} {phase}"));
+                    }
+                }
+            }
+        }
+        for decoder in distinct_decoders(configs) {
+            headers.push(format!("Decoded Size {decoder} Metadata (bytes)"));
+            headers.push(format!("Decoded Size {decoder} With Page Indexes (bytes)"));
+        }
+        headers
     }
 
     pub fn to_csv_row(&self) -> Vec<String> {
-        vec![
+        let mut row = vec![
             self.description.clone(),
-            self.arrow_56_timing.avg_metadata_parsing_duration().as_nanos().to_string(),
-            self.arrow_56_timing.avg_index_parsing_duration().as_nanos().to_string(),
-            self.arrow_57_timing.avg_metadata_parsing_duration().as_nanos().to_string(),
-            self.arrow_57_timing.avg_index_parsing_duration().as_nanos().to_string(),
-            self.arrow_57_timing_no_stats.avg_metadata_parsing_duration().as_nanos().to_string(),
-            self.arrow_57_timing_no_stats.avg_index_parsing_duration().as_nanos().to_string(),
-        ]
+            self.writer_version.to_string(),
+            self.writer_crate.to_string(),
+            self.columns.to_string(),
+            self.row_groups.to_string(),
+            self.seed.to_string(),
+            self.metadata_size.to_string(),
+            self.index_size.to_string(),
+        ];
+        for (config, timing) in &self.timings {
+            match timing {
+                Some(timing) => {
+                    row.push(
+                        timing
+                            .avg_metadata_parsing_duration()
+                            .as_nanos()
+                            .to_string(),
+                    );
+                    if config.combined_index_time() {
+                        row.push(String::new());
+                    } else {
+                        row.push(timing.avg_index_parsing_duration().as_nanos().to_string());
+                    }
+                }
+                None => row.extend([String::new(), String::new()]),
+            }
+        }
+        if allocations::ENABLED {
+            for (_, timing) in &self.timings {
+                match timing.as_ref().and_then(Timing::avg_allocations) {
+                    Some(allocations) => {
+                        row.push(allocations.allocations.to_string());
+                        row.push(allocations.deallocations.to_string());
+                        row.push(allocations.bytes.to_string());
+                    }
+                    None => row.extend([String::new(), String::new(), String::new()]),
+                }
+            }
+        }
+        if perf_counters::ENABLED {
+            for (_, timing) in &self.timings {
+                match timing.as_ref().and_then(Timing::avg_perf) {
+                    Some(perf) => {
+                        for counts in [perf.metadata, perf.index] {
+                            row.extend(perf_counter_values(&counts).map(|value| value.to_string()));
+                        }
+                    }
+                    None => row.extend(vec![String::new(); 2 * PERF_COUNTERS.len()]),
+                }
+            }
+        }
+        for (_, memory_size) in &self.memory_sizes {
+            match memory_size {
+                Some(memory_size) => {
+                    row.push(memory_size.metadata.to_string());
+                    row.push(memory_size.with_page_indexes.to_string());
+                }
+                None => row.extend([String::new(), String::new()]),
+            }
+        }
+        row
+    }
+
+    /// Average metadata and page index parse times of each decoder
+    fn timing_cells(&self) -> Vec<String> {
+        self.timings
+            .iter()
+            .flat_map(|(config, timing)| match timing {
+                Some(timing) => [
+                    format!("{:?}", timing.avg_metadata_parsing_duration()),
+                    config.index_cell(timing),
+                ],
+                None => ["-".to_string(), "-".to_string()],
+            })
+            .collect()
     }
 }
 
 impl MetadataParseResult {
     pub fn add_to_table(&self, table: &mut Table) {
-        table.add_row(vec![
+        let mut row = vec![
+            self.description.clone(),
+            self.writer_version.to_string(),
+            self.writer_crate.to_string(),
+            self.metadata_size.to_string(),
+            self.index_size.to_string(),
+        ];
+        row.extend(self.timing_cells());
+        if allocations::ENABLED {
+            for (_, timing) in &self.timings {
+                let cell = match timing.as_ref().and_then(Timing::avg_allocations) {
+                    Some(allocations) => {
+                        format!(
+                            "{} allocs\n{} bytes",
+                            allocations.allocations, allocations.bytes
+                        )
+                    }
+                    None => "-".to_string(),
+                };
+                row.push(cell);
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+/// Names of the hardware counters, in the order of [`perf_counter_values`]
+const PERF_COUNTERS: [&str; 5] = [
+    "Instructions",
+    "Cycles",
+    "Branch Misses",
+    "L1D Misses",
+    "LLC Misses",
+];
+
+fn perf_counter_values(counts: &PerfCounts) -> [u64; 5] {
+    [
+        counts.instructions,
+        counts.cycles,
+        counts.branch_misses,
+        counts.l1d_misses,
+        counts.llc_misses,
+    ]
+}
+
+impl MetadataParseResult {
+    /// Headers for a table of the hardware counters of each decoder
+    pub fn set_perf_counter_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        let mut headers = vec!["Description".to_string()];
+        for config in configs {
+            headers.push(format!("{config}\n\n{}", config.metadata_label()));
+            headers.push(format!("{config}\n\n{}", config.index_label()));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds the average hardware counters per run of each decoder, if read
+    pub fn add_perf_counter_row(&self, table: &mut Table) {
+        let mut row = vec![self.description.clone()];
+        for (_, timing) in &self.timings {
+            match timing.as_ref().and_then(Timing::avg_perf) {
+                Some(perf) => {
+                    for counts in [perf.metadata, perf.index] {
+                        let lines: Vec<String> = PERF_COUNTERS
+                            .iter()
+                            .zip(perf_counter_values(&counts))
+                            .map(|(counter, value)| format!("{value} {counter}"))
+                            .collect();
+                        row.push(lines.join("\n"));
+                    }
+                }
+                None => row.extend(["-".to_string(), "-".to_string()]),
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+/// Formats a change from `base` to `other` as `base -> other (+x.x%)`
+fn format_change(base: impl Debug, other: impl Debug, base_value: f64, other_value: f64) -> String {
+    let change = (other_value - base_value) / base_value * 100.0;
+    format!("{base:?} -> {other:?} ({change:+.1}%)")
+}
+
+impl MetadataParseResult {
+    /// Headers for a table comparing the same spec written by different parquet
+    /// crates or with encryption
+    pub fn set_writer_comparison_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        table.set_header(with_timing_headers(
+            &["Description", "Compared", "Metadata Size", "Index Size"],
+            configs,
+        ));
+    }
+
+    /// Adds a row comparing this result to `other`, for a file written from the
+    /// same spec by a different parquet crate or with different encryption
+    pub fn add_writer_comparison_row(&self, other: &Self, table: &mut Table) {
+        let durations = |base: Duration, other: Duration| {
+            format_change(base, other, base.as_nanos() as f64, other.as_nanos() as f64)
+        };
+        let mut compared = vec![];
+        if self.writer_crate != other.writer_crate {
+            compared.push(format!("{} -> {}", self.writer_crate, other.writer_crate));
+        }
+        if self.encryption != other.encryption {
+            compared.push(format!("{} -> {}", self.encryption, other.encryption));
+        }
+        let mut row = vec![
+            self.description.clone(),
+            compared.join(", "),
+            format_change(
+                self.metadata_size,
+                other.metadata_size,
+                self.metadata_size as f64,
+                other.metadata_size as f64,
+            ),
+            format_change(
+                self.index_size,
+                other.index_size,
+                self.index_size as f64,
+                other.index_size as f64,
+            ),
+        ];
+        for ((config, timing), (_, other_timing)) in self.timings.iter().zip(&other.timings) {
+            let (Some(timing), Some(other_timing)) = (timing, other_timing) else {
+                row.extend(["-".to_string(), "-".to_string()]);
+                continue;
+            };
+            row.push(durations(
+                timing.avg_metadata_parsing_duration(),
+                other_timing.avg_metadata_parsing_duration(),
+            ));
+            if config.combined_index_time() {
+                row.push("-".to_string());
+            } else {
+                row.push(durations(
+                    timing.avg_index_parsing_duration(),
+                    other_timing.avg_index_parsing_duration(),
+                ));
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table showing how parse time scales with the number of
+    /// row groups, for files with the same number of columns
+    pub fn set_scaling_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        table.set_header(with_timing_headers(
+            &["Row Groups", "Column Chunks", "Metadata Size", "Index Size"],
+            configs,
+        ));
+    }
+
+    /// Adds a row for this result to a row group scaling table
+    pub fn add_scaling_row(&self, table: &mut Table) {
+        let mut row = vec![
+            self.row_groups.to_string(),
+            (self.columns * self.row_groups).to_string(),
+            self.metadata_size.to_string(),
+            self.index_size.to_string(),
+        ];
+        row.extend(self.timing_cells());
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table comparing the metadata parse time of re-encoded
+    /// footers with statistics for a subset of the columns to the re-encoded
+    /// footer with all statistics and to the "no stats" fork on that footer, and
+    /// the "no stats" fork to released Arrow 57 on the original footer
+    pub fn set_projection_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        let mut headers = vec!["Description".to_string()];
+        for config in configs
+            .iter()
+            .filter(|config| config.decoder.in_projection_table())
+        {
+            headers.push(format!("Parse Time {config}\n\nMetadata"));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the metadata parse times of the projection decoders, and
+    /// the gap of each to its baselines with the same page index policy: the
+    /// footer re-encoded with all statistics and the "no stats" fork on that
+    /// footer for the projected footers, the footer re-encoded with all
+    /// statistics for the fork on it, and the original footer (Arrow 57) for the
+    /// fork on the original footer
+    pub fn add_projection_row(&self, table: &mut Table) {
+        let metadata_duration = |config: DecoderConfig| {
+            self.timings
+                .iter()
+                .find(|(timing_config, _)| *timing_config == config)
+                .and_then(|(_, timing)| timing.as_ref())
+                .map(|timing| timing.avg_metadata_parsing_duration())
+        };
+        let mut row = vec![self.description.clone()];
+        for (config, timing) in &self.timings {
+            if !config.decoder.in_projection_table() {
+                continue;
+            }
+            let Some(timing) = timing else {
+                row.push("-".to_string());
+                continue;
+            };
+            let duration = timing.avg_metadata_parsing_duration();
+            let baselines: &[(Decoder, &str)] = match config.decoder {
+                Decoder::Arrow57Projected(percent) if percent < 100 => &[
+                    (Decoder::Arrow57Projected(100), "all stats"),
+                    (Decoder::Arrow57NoStatsReencoded, "no stats"),
+                ],
+                Decoder::Arrow57NoStatsReencoded => {
+                    &[(Decoder::Arrow57Projected(100), "all stats")]
+                }
+                Decoder::Arrow57NoStats => &[(Decoder::Arrow57, "Arrow 57")],
+                _ => &[],
+            };
+            let gaps: Vec<_> = baselines
+                .iter()
+                .filter_map(|(decoder, name)| {
+                    let baseline = metadata_duration(DecoderConfig {
+                        decoder: *decoder,
+                        page_index_policy: config.page_index_policy,
+                    })?;
+                    let gap = (duration.as_nanos() as f64 - baseline.as_nanos() as f64)
+                        / baseline.as_nanos() as f64
+                        * 100.0;
+                    Some(format!("{gap:+.1}% vs {name}"))
+                })
+                .collect();
+            if gaps.is_empty() {
+                row.push(format!("{duration:?}"));
+            } else {
+                row.push(format!("{duration:?} ({})", gaps.join(", ")));
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table comparing the page index parse time of each decoder
+    /// when loading both page indexes, only the column index, or only the
+    /// offset index
+    pub fn set_page_index_headers(table: &mut Table, decoders: &[Decoder]) {
+        let mut headers = vec!["Description".to_string()];
+        for decoder in decoders
+            .iter()
+            .filter(|decoder| decoder.times_page_indexes())
+        {
+            headers.push(format!("{decoder}\n\nColumn + Offset Index"));
+            headers.push(format!("{decoder}\n\nColumn Index only"));
+            headers.push(format!("{decoder}\n\nOffset Index only"));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the page index parse time of each decoder for each page
+    /// index policy, and the savings compared to loading both page indexes
+    pub fn add_page_index_row(&self, decoders: &[Decoder], table: &mut Table) {
+        let index_duration = |decoder: Decoder, page_index_policy: PageIndexPolicy| {
+            let config = DecoderConfig {
+                decoder,
+                page_index_policy,
+            };
+            self.timings
+                .iter()
+                .find(|(timing_config, _)| *timing_config == config)
+                .and_then(|(_, timing)| timing.as_ref())
+                .map(|timing| timing.avg_index_parsing_duration())
+        };
+        let mut row = vec![self.description.clone()];
+        for decoder in decoders
+            .iter()
+            .filter(|decoder| decoder.times_page_indexes())
+        {
+            let both = index_duration(*decoder, PageIndexPolicy::Both);
+            row.push(both.map_or("-".to_string(), |both| format!("{both:?}")));
+            for policy in [PageIndexPolicy::ColumnIndex, PageIndexPolicy::OffsetIndex] {
+                let cell = match (both, index_duration(*decoder, policy)) {
+                    (Some(both), Some(duration)) => format_change(
+                        both,
+                        duration,
+                        both.as_nanos() as f64,
+                        duration.as_nanos() as f64,
+                    ),
+                    (None, Some(duration)) => format!("{duration:?}"),
+                    (_, None) => "-".to_string(),
+                };
+                row.push(cell);
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Average metadata and page index parse time of `config`, if it was run
+    pub fn parse_duration(&self, config: DecoderConfig) -> Option<Duration> {
+        self.timings
+            .iter()
+            .find(|(timing_config, _)| *timing_config == config)
+            .and_then(|(_, timing)| timing.as_ref())
+            .map(|timing| {
+                timing.avg_metadata_parsing_duration() + timing.avg_index_parsing_duration()
+            })
+    }
+
+    /// Headers for a table comparing the synchronous readers to the push decoder
+    /// of the same crate
+    pub fn set_reader_comparison_headers(table: &mut Table, configs: &[DecoderConfig]) {
+        let mut headers = vec!["Description".to_string()];
+        for config in configs
+            .iter()
+            .filter(|config| config.decoder.push_decoder().is_some())
+        {
+            headers.push(format!("{config}\n\n{}", config.metadata_label()));
+            headers.push(format!("{config}\n\n{}", config.index_label()));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the parse times of each synchronous reader, compared to
+    /// the push decoder of the same crate with the same page index policy
+    pub fn add_reader_comparison_row(&self, table: &mut Table) {
+        let durations = |base: Duration, other: Duration| {
+            format_change(base, other, base.as_nanos() as f64, other.as_nanos() as f64)
+        };
+        let mut row = vec![self.description.clone()];
+        for (config, timing) in &self.timings {
+            let Some(push_decoder) = config.decoder.push_decoder() else {
+                continue;
+            };
+            let Some(timing) = timing else {
+                row.extend(["-".to_string(), "-".to_string()]);
+                continue;
+            };
+            let push_config = DecoderConfig {
+                decoder: push_decoder,
+                page_index_policy: config.page_index_policy,
+            };
+            let push_timing = self
+                .timings
+                .iter()
+                .find(|(other, _)| *other == push_config)
+                .and_then(|(_, push_timing)| push_timing.as_ref());
+            match push_timing {
+                // compare the combined time to the push decoder's total
+                Some(push_timing) if config.combined_index_time() => {
+                    row.push(durations(
+                        push_timing.avg_metadata_parsing_duration()
+                            + push_timing.avg_index_parsing_duration(),
+                        timing.avg_metadata_parsing_duration(),
+                    ));
+                    row.push("-".to_string());
+                }
+                Some(push_timing) => {
+                    row.push(durations(
+                        push_timing.avg_metadata_parsing_duration(),
+                        timing.avg_metadata_parsing_duration(),
+                    ));
+                    row.push(durations(
+                        push_timing.avg_index_parsing_duration(),
+                        timing.avg_index_parsing_duration(),
+                    ));
+                }
+                None => {
+                    row.push(format!("{:?}", timing.avg_metadata_parsing_duration()));
+                    row.push(config.index_cell(timing));
+                }
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table of the round trips and over-read bytes of `decoder`
+    /// for each prefetch hint
+    pub fn set_round_trip_headers(table: &mut Table, prefetch_hints: &[u64]) {
+        let mut headers = vec![
+            "Description".to_string(),
+            "Columns".to_string(),
+            "Metadata + Index Size".to_string(),
+        ];
+        for prefetch in prefetch_hints {
+            headers.push(format!("Prefetch {prefetch}\n\nRound Trips"));
+            headers.push(format!("Prefetch {prefetch}\n\nOver-read"));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the round trips and over-read bytes of `decoder` for
+    /// each of `prefetch_hints`, or `-` if they were not counted (the push
+    /// decoders can not read encrypted files)
+    pub fn add_round_trip_row(&self, decoder: Decoder, prefetch_hints: &[u64], table: &mut Table) {
+        let mut row = vec![
+            self.description.clone(),
+            self.columns.to_string(),
+            (self.metadata_size + self.index_size).to_string(),
+        ];
+        for prefetch in prefetch_hints {
+            let round_trips = self
+                .round_trips
+                .iter()
+                .find(|(round_trip_decoder, round_trips)| {
+                    *round_trip_decoder == decoder && round_trips.prefetch == *prefetch
+                });
+            match round_trips {
+                Some((_, round_trips)) => {
+                    row.push(round_trips.round_trips.to_string());
+                    row.push(round_trips.over_read.to_string());
+                }
+                None => row.extend(vec!["-".to_string(); 2]),
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table of the time to encode the decoded metadata with the
+    /// metadata writer of each of `writers`, and the encoded sizes
+    pub fn set_metadata_write_headers(table: &mut Table, writers: &[WriterCrate]) {
+        let mut headers = vec![
+            "Description".to_string(),
+            "Metadata Size".to_string(),
+            "Index Size".to_string(),
+        ];
+        for writer in writers {
+            headers.push(format!("Write Time {writer}\n\nMetadata + Index"));
+            headers.push(format!("Encoded Size {writer}\n\nMetadata"));
+            headers.push(format!("Encoded Size {writer}\n\nIndex"));
+            if allocations::ENABLED {
+                headers.push(format!("Allocations {writer}\n\nMetadata + Index"));
+            }
+            if perf_counters::ENABLED {
+                headers.push(format!("Instructions {writer}\n\nMetadata + Index"));
+            }
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the write time of each metadata writer, and the encoded
+    /// sizes compared to the sizes in the file
+    pub fn add_metadata_write_row(&self, table: &mut Table) {
+        let sizes =
+            |base: usize, other: usize| format_change(base, other, base as f64, other as f64);
+        let mut row = vec![
             self.description.clone(),
-            format!("{:?}", self.arrow_56_timing.avg_metadata_parsing_duration()),
-            format!("{:?}", self.arrow_56_timing.avg_index_parsing_duration()),
-            format!("{:?}", self.arrow_57_timing.avg_metadata_parsing_duration()),
-            format!("{:?}", self.arrow_57_timing.avg_index_parsing_duration()),
-            format!("{:?}", self.arrow_57_timing_no_stats.avg_metadata_parsing_duration()),
-            format!("{:?}", self.arrow_57_timing_no_stats.avg_index_parsing_duration()),
-        ]);
+            self.metadata_size.to_string(),
+            self.index_size.to_string(),
+        ];
+        for (_, metadata_write) in &self.metadata_writes {
+            match metadata_write {
+                Some(metadata_write) => {
+                    row.push(format!("{:?}", metadata_write.avg_duration()));
+                    row.push(sizes(self.metadata_size, metadata_write.metadata_size));
+                    row.push(sizes(self.index_size, metadata_write.index_size));
+                    let timing = &metadata_write.timing;
+                    if allocations::ENABLED {
+                        row.push(timing.avg_allocations().map_or("-".to_string(), |counts| {
+                            format!("{} ({} bytes)", counts.allocations, counts.bytes)
+                        }));
+                    }
+                    if perf_counters::ENABLED {
+                        row.push(timing.avg_perf().map_or("-".to_string(), |perf| {
+                            perf.metadata.instructions.to_string()
+                        }));
+                    }
+                }
+                None => {
+                    let cells =
+                        3 + usize::from(allocations::ENABLED) + usize::from(perf_counters::ENABLED);
+                    row.extend(vec!["-".to_string(); cells]);
+                }
+            }
+        }
+        table.add_row(row);
+    }
+}
+
+impl MetadataParseResult {
+    /// Headers for a table of the heap size of the metadata decoded by each of
+    /// `decoders`, and the amplification compared to the encoded size
+    pub fn set_memory_size_headers(table: &mut Table, decoders: &[Decoder]) {
+        let mut headers = vec![
+            "Description".to_string(),
+            "Metadata Size".to_string(),
+            "Index Size".to_string(),
+        ];
+        for decoder in decoders {
+            headers.push(format!("Decoded Size {decoder}\n\nMetadata"));
+            headers.push(format!("Decoded Size {decoder}\n\nWith Page Indexes"));
+        }
+        table.set_header(headers);
+    }
+
+    /// Adds a row with the decoded metadata size of each decoder, without page
+    /// indexes (relative to the metadata size) and with page indexes (relative
+    /// to the metadata and index size)
+    pub fn add_memory_size_row(&self, table: &mut Table) {
+        let amplification = |decoded: usize, encoded: usize| {
+            format!("{decoded} ({:.1}x)", decoded as f64 / encoded as f64)
+        };
+        let mut row = vec![
+            self.description.clone(),
+            self.metadata_size.to_string(),
+            self.index_size.to_string(),
+        ];
+        for (_, memory_size) in &self.memory_sizes {
+            match memory_size {
+                Some(memory_size) => {
+                    row.push(amplification(memory_size.metadata, self.metadata_size));
+                    row.push(amplification(
+                        memory_size.with_page_indexes,
+                        self.metadata_size + self.index_size,
+                    ));
+                }
+                None => row.extend(["-".to_string(), "-".to_string()]),
+            }
+        }
+        table.add_row(row);
     }
 }
 
@@ -388,14 +1946,100 @@ impl Display for MetadataParseResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "MetadataParseResult:")?;
         writeln!(f, "  description: {}", self.description)?;
-        writeln!(f, "  Arrow 56 Timing:")?;
-        writeln!(f, "{}", self.arrow_56_timing)?;
-        writeln!(f, "  Arrow 57 Timing:")?;
-        writeln!(f, "{}", self.arrow_57_timing)?;
-        writeln!(f, "  Arrow 57 Timing (no stats):")?;
-        writeln!(f, "{}", self.arrow_57_timing_no_stats)?;
+        writeln!(f, "  writer version: {}", self.writer_version)?;
+        writeln!(f, "  writer crate: {}", self.writer_crate)?;
+        writeln!(f, "  columns: {}", self.columns)?;
+        writeln!(f, "  row groups: {}", self.row_groups)?;
+        writeln!(f, "  seed: {}", self.seed)?;
+        writeln!(f, "  encryption: {}", self.encryption)?;
+        writeln!(f, "  metadata size: {} bytes", self.metadata_size)?;
+        writeln!(f, "  index size: {} bytes", self.index_size)?;
+        for (config, timing) in &self.timings {
+            match timing {
+                Some(timing) => {
+                    writeln!(f, "  {config} Timing:")?;
+                    writeln!(f, "{timing}")?;
+                }
+                None => writeln!(f, "  {config}: can not read this file")?,
+            }
+        }
+        for (decoder, round_trips) in &self.round_trips {
+            writeln!(
+                f,
+                "  {decoder} prefetch {}: {} round trips, {} bytes fetched, {} over-read",
+                round_trips.prefetch,
+                round_trips.round_trips,
+                round_trips.bytes_fetched,
+                round_trips.over_read
+            )?;
+        }
+        for (decoder, memory_size) in &self.memory_sizes {
+            if let Some(memory_size) = memory_size {
+                writeln!(
+                    f,
+                    "  {decoder} decoded size: {} bytes, {} bytes with page indexes",
+                    memory_size.metadata, memory_size.with_page_indexes
+                )?;
+            }
+        }
+        for (writer_crate, metadata_write) in &self.metadata_writes {
+            if let Some(metadata_write) = metadata_write {
+                writeln!(
+                    f,
+                    "  {writer_crate} metadata writer: {:?}, {} bytes metadata, {} bytes index",
+                    metadata_write.avg_duration(),
+                    metadata_write.metadata_size,
+                    metadata_write.index_size
+                )?;
+            }
+        }
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn projection_mask_selects_rounded_percentage() {
+        for (columns, percent, selected) in [
+            (1000, 1, 10),
+            (1000, 10, 100),
+            (1000, 50, 500),
+            (1000, 100, 1000),
+            (30, 10, 3),
+            // 0.5 columns round up, 0.4 round down
+            (5, 10, 1),
+            (4, 10, 0),
+            (100, 150, 100),
+        ] {
+            let mask = projection_mask(columns, percent);
+            assert_eq!(mask.len(), columns);
+            let count = mask.iter().filter(|projected| **projected).count();
+            assert_eq!(count, selected, "{percent}% of {columns} columns");
+        }
+        // spread evenly rather than the first columns
+        let mask = projection_mask(10, 20);
+        let projected: Vec<_> = (0..10).filter(|column| mask[*column]).collect();
+        assert_eq!(projected, vec![4, 9]);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)] // each vec is one round trip
+    fn fetched_bytes_counts_over_read() {
+        // one fetch of exactly the tail
+        assert_eq!(fetched_bytes(&[vec![100..200]], 100), (100, 0));
+        // prefetch reaching before the tail, then the page indexes again
+        assert_eq!(
+            fetched_bytes(&[vec![50..200], vec![100..150]], 100),
+            (200, 100)
+        );
+        // overlapping and disjoint ranges in one round trip
+        assert_eq!(
+            fetched_bytes(&[vec![150..200, 100..120], vec![110..160]], 100),
+            (120, 20)
+        );
+        assert_eq!(fetched_bytes(&[], 100), (0, 0));
+    }
+}
@@ -0,0 +1,76 @@
+//! Parquet modular encryption with local test keys
+//!
+//! Encrypted files are written with [`FOOTER_KEY`] (and [`COLUMN_KEY`] for
+//! every column of files with a plaintext footer), and the decoders are given
+//! the same keys. The keys are only for benchmarking and are not secret.
+//!
+//! The parquet crates differ in how they pass the encryption properties around
+//! (by value, by reference or in an `Arc`), so the properties are built by
+//! macros for each crate.
+
+/// Key of the footer, and of every column of files with an encrypted footer
+pub const FOOTER_KEY: &[u8; 16] = b"0123456789012345";
+
+/// Key of every column of files with a plaintext footer
+pub const COLUMN_KEY: &[u8; 16] = b"1234567890123450";
+
+/// Macro that returns `$builder` (a `WriterPropertiesBuilder` of the `$parquet`
+/// crate) configured to write files with `$encryption`. `$column_names` are
+/// the names of all the columns
+macro_rules! with_encryption {
+    ($parquet:ident, $builder:expr, $encryption:expr, $column_names:expr) => {{
+        use crate::encryption::{COLUMN_KEY, FOOTER_KEY};
+        use crate::writer_options::Encryption;
+        use $parquet::encryption::encrypt::FileEncryptionProperties;
+        let builder = $builder;
+        let column_names: &[String] = $column_names;
+        match $encryption {
+            Encryption::None => builder,
+            Encryption::EncryptedFooter => {
+                let properties = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+                    .build()
+                    .unwrap();
+                builder.with_file_encryption_properties(properties.into())
+            }
+            Encryption::PlaintextFooter => {
+                let mut properties = FileEncryptionProperties::builder(FOOTER_KEY.to_vec())
+                    .with_plaintext_footer(true);
+                for column_name in column_names {
+                    properties = properties.with_column_key(column_name, COLUMN_KEY.to_vec());
+                }
+                builder.with_file_encryption_properties(properties.build().unwrap().into())
+            }
+        }
+    }};
+}
+pub(crate) use with_encryption;
+
+/// Macro that returns the `Option<Arc<FileDecryptionProperties>>` of the
+/// `$parquet` crate to read files with `$encryption`. `$column_names` are the
+/// names of all the columns
+macro_rules! decryption_properties {
+    ($parquet:ident, $encryption:expr, $column_names:expr) => {{
+        use crate::encryption::{COLUMN_KEY, FOOTER_KEY};
+        use crate::writer_options::Encryption;
+        use $parquet::encryption::decrypt::FileDecryptionProperties;
+        let column_names: &[String] = $column_names;
+        let properties = match $encryption {
+            Encryption::None => None,
+            Encryption::EncryptedFooter => Some(
+                FileDecryptionProperties::builder(FOOTER_KEY.to_vec())
+                    .build()
+                    .unwrap(),
+            ),
+            Encryption::PlaintextFooter => {
+                let mut properties = FileDecryptionProperties::builder(FOOTER_KEY.to_vec());
+                for column_name in column_names {
+                    properties = properties.with_column_key(column_name, COLUMN_KEY.to_vec());
+                }
+                Some(properties.build().unwrap())
+            }
+        };
+        properties
+            .map(|properties| -> std::sync::Arc<FileDecryptionProperties> { properties.into() })
+    }};
+}
+pub(crate) use decryption_properties;
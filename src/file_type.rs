@@ -1,36 +1,45 @@
 use crate::datagen;
+use crate::writer_options::ColumnEncoding;
 use arrow::array::{ArrayRef, RecordBatch};
-use arrow::datatypes::{DataType, Field, Float32Type, Schema};
+use arrow::datatypes::{DataType, Field, Float32Type, Int64Type, Schema};
+use serde::Deserialize;
 use std::fmt::Display;
 use std::sync::Arc;
 
 /// The type of file to create
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 pub enum FileType {
     Float,  // float32 columns
     String, // utf8 string columns
+    Int64,  // int64 columns
 }
 impl Display for FileType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FileType::Float => write!(f, "Float32"),
             FileType::String => write!(f, "String"),
+            FileType::Int64 => write!(f, "Int64"),
         }
     }
 }
 
 impl FileType {
-    pub fn create_batch(&self, seed: usize, num_rows: usize, columns: usize) -> RecordBatch {
-        let null_density = 0.0001;
+    pub fn create_batch(
+        &self,
+        seed: u64,
+        num_rows: usize,
+        columns: usize,
+        null_density: f32,
+    ) -> RecordBatch {
         let mut arrays: Vec<ArrayRef> = vec![];
         match self {
             FileType::Float => {
                 for i in 0..columns {
-                    let array_seed = seed * columns + i;
+                    let array_seed = array_seed(seed, columns, i);
                     let array = datagen::create_primitive_array_with_seed::<Float32Type>(
                         num_rows,
                         null_density,
-                        array_seed as u64,
+                        array_seed,
                     );
                     arrays.push(Arc::new(array));
                 }
@@ -38,12 +47,23 @@ impl FileType {
             FileType::String => {
                 let max_str_len = 20;
                 for i in 0..columns {
-                    let array_seed = seed * columns + i;
+                    let array_seed = array_seed(seed, columns, i);
                     let array = datagen::create_string_array_with_max_len::<i32>(
                         num_rows,
                         null_density,
                         max_str_len,
-                        array_seed as u64,
+                        array_seed,
+                    );
+                    arrays.push(Arc::new(array));
+                }
+            }
+            FileType::Int64 => {
+                for i in 0..columns {
+                    let array_seed = array_seed(seed, columns, i);
+                    let array = datagen::create_primitive_array_with_seed::<Int64Type>(
+                        num_rows,
+                        null_density,
+                        array_seed,
                     );
                     arrays.push(Arc::new(array));
                 }
@@ -66,6 +86,42 @@ impl FileType {
                     .collect();
                 Arc::new(Schema::new(fields))
             }
+            FileType::Int64 => {
+                let fields: Vec<Field> = (0..columns)
+                    .map(|i| Field::new(format!("col_{i}"), DataType::Int64, true))
+                    .collect();
+                Arc::new(Schema::new(fields))
+            }
+        }
+    }
+
+    /// Approximate size of one value when encoded, in bytes
+    pub fn estimated_value_size(&self) -> usize {
+        match self {
+            FileType::Float => 4,
+            // average string length is 10 plus a 4 byte length
+            FileType::String => 14,
+            FileType::Int64 => 8,
         }
     }
+
+    /// Returns true if the parquet writer can encode columns of this type with `encoding`
+    pub fn supports_encoding(&self, encoding: ColumnEncoding) -> bool {
+        matches!(
+            (self, encoding),
+            (_, ColumnEncoding::Plain | ColumnEncoding::Dictionary)
+                | (FileType::Int64, ColumnEncoding::DeltaBinaryPacked)
+                | (
+                    FileType::Float | FileType::Int64,
+                    ColumnEncoding::ByteStreamSplit
+                )
+        )
+    }
+}
+
+/// Seed for the data of column `column` of a batch generated with `seed`. Wraps
+/// around rather than overflowing for large seeds
+fn array_seed(seed: u64, columns: usize, column: usize) -> u64 {
+    seed.wrapping_mul(columns as u64)
+        .wrapping_add(column as u64)
 }
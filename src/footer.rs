@@ -0,0 +1,137 @@
+//! Utilities for rewriting the footer (thrift `FileMetaData`) of parquet files
+//!
+//! The writer does not offer options for every field in the footer, so some
+//! fields are removed after the file is written by decoding the footer,
+//! modifying the `ParquetMetaData` and encoding it again. The data pages and
+//! page indexes are not modified, so their offsets remain valid.
+//!
+//! There is one module per parquet version so a file's footer is re-encoded
+//! by the same thrift encoder that originally wrote it.
+
+/// Size of the footer tail (metadata length + `PAR1` magic)
+const FOOTER_SIZE: u64 = 8;
+
+/// Generates the footer rewriting functions for a version of the parquet crate,
+/// followed by the `$extra` items that only that version needs
+macro_rules! footer_module {
+    ($module:ident, $parquet:ident $(; $($extra:item)*)?) => {
+        pub mod $module {
+            use super::FOOTER_SIZE;
+            use std::fs::File;
+            use std::io::{Read, Seek, SeekFrom};
+            use $parquet::file::metadata::{
+                ColumnChunkMetaData, ParquetMetaData, ParquetMetaDataReader, ParquetMetaDataWriter,
+            };
+
+            /// Replaces the footer of `file` with the result of calling `f` on the
+            /// decoded metadata (without page indexes)
+            pub fn rewrite_footer(
+                file: &mut File,
+                f: impl FnOnce(ParquetMetaData) -> ParquetMetaData,
+            ) {
+                let file_len = file.metadata().unwrap().len();
+                let mut tail = [0u8; FOOTER_SIZE as usize];
+                file.seek(SeekFrom::Start(file_len - FOOTER_SIZE)).unwrap();
+                file.read_exact(&mut tail).unwrap();
+                // deprecated in parquet 57, but parquet 56 has no `FooterTail::try_from`
+                #[allow(deprecated)]
+                let footer_tail = ParquetMetaDataReader::decode_footer_tail(&tail).unwrap();
+
+                let metadata_start = file_len - FOOTER_SIZE - footer_tail.metadata_length() as u64;
+                let mut metadata_bytes = vec![0u8; footer_tail.metadata_length()];
+                file.seek(SeekFrom::Start(metadata_start)).unwrap();
+                file.read_exact(&mut metadata_bytes).unwrap();
+                let metadata = ParquetMetaDataReader::decode_metadata(&metadata_bytes).unwrap();
+
+                // metadata has no page indexes loaded, so the writer only writes the
+                // footer and keeps the existing page index offsets
+                let metadata = f(metadata);
+                file.set_len(metadata_start).unwrap();
+                file.seek(SeekFrom::Start(metadata_start)).unwrap();
+                ParquetMetaDataWriter::new(&mut *file, &metadata)
+                    .finish()
+                    .unwrap();
+            }
+
+            /// Removes the `encoding_stats` from every column chunk
+            pub fn clear_page_encoding_stats(metadata: ParquetMetaData) -> ParquetMetaData {
+                map_column_chunks(metadata, |_, column| {
+                    column
+                        .into_builder()
+                        .clear_page_encoding_stats()
+                        .build()
+                        .unwrap()
+                })
+            }
+
+            /// Replaces every column chunk with the result of calling `f` with its
+            /// column index
+            fn map_column_chunks(
+                metadata: ParquetMetaData,
+                f: impl Fn(usize, ColumnChunkMetaData) -> ColumnChunkMetaData,
+            ) -> ParquetMetaData {
+                let mut builder = metadata.into_builder();
+                let row_groups = builder
+                    .take_row_groups()
+                    .into_iter()
+                    .map(|row_group| {
+                        let columns = row_group
+                            .columns()
+                            .iter()
+                            .enumerate()
+                            .map(|(column_index, column)| f(column_index, column.clone()))
+                            .collect();
+                        row_group
+                            .into_builder()
+                            .set_column_metadata(columns)
+                            .build()
+                            .unwrap()
+                    })
+                    .collect();
+                builder.set_row_groups(row_groups).build()
+            }
+
+            $($($extra)*)?
+        }
+    };
+}
+
+footer_module!(v56, parquet_56);
+footer_module!(
+    v57,
+    parquet_57;
+    /// Decodes the footer metadata in `metadata_bytes`, and returns the encoded
+    /// result of calling `f` on it followed by the footer tail (the end of a file)
+    pub fn reencode_footer(
+        metadata_bytes: &[u8],
+        f: impl FnOnce(ParquetMetaData) -> ParquetMetaData,
+    ) -> Vec<u8> {
+        let metadata = ParquetMetaDataReader::decode_metadata(metadata_bytes).unwrap();
+        let mut buffer = vec![];
+        ParquetMetaDataWriter::new(&mut buffer, &f(metadata))
+            .finish()
+            .unwrap();
+        buffer
+    }
+
+    /// Removes the statistics and column index of the column chunks of every
+    /// column that is not set in `projection`, as if only the projected columns
+    /// were decoded
+    pub fn clear_unprojected_statistics(
+        metadata: ParquetMetaData,
+        projection: &[bool],
+    ) -> ParquetMetaData {
+        map_column_chunks(metadata, |column_index, column| {
+            if projection[column_index] {
+                return column;
+            }
+            column
+                .into_builder()
+                .clear_statistics()
+                .set_column_index_offset(None)
+                .set_column_index_length(None)
+                .build()
+                .unwrap()
+        })
+    }
+);
@@ -1,67 +1,215 @@
+mod allocations;
 mod benchmark;
 mod datagen;
+mod encryption;
 mod file_type;
+mod footer;
+mod manifest;
+mod matrix;
+mod memory;
 mod parquet_file;
+mod perf_counters;
+mod remote;
+mod synthetic;
+mod tail_bytes;
+mod timed_region;
+mod validation;
+mod writer_options;
 
-use crate::benchmark::{MetadataParseBenchmark, MetadataParseResult};
-use crate::file_type::FileType;
-use crate::parquet_file::ParquetFileSpecBuilder;
+use crate::benchmark::{
+    Decoder, DecoderConfig, MetadataParseBenchmark, MetadataParseResult, PageIndexPolicy,
+    distinct_decoders,
+};
+use crate::matrix::Matrix;
+use crate::parquet_file::create_all;
+use crate::remote::{ObjectStoreBenchmark, ObjectStoreResult};
 use comfy_table::Table;
 use std::fs;
 use std::path::PathBuf;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
-    let output_dir = PathBuf::from("output");
-    fs::create_dir_all(&output_dir).unwrap();
-
-    let mut specs = vec![];
-    for file_type in [FileType::Float, FileType::String] {
-    //for file_type in [FileType::String] {
-        //for columns in [100, 1000, 10000] {
-        for columns in [100, 1000, 10000, 100000] {
-            specs.push(
-                ParquetFileSpecBuilder::new()
-                    .with_path(output_dir.join(format!("{file_type}_data_{columns}_cols.parquet")))
-                    .with_file_type(file_type)
-                    .with_columns(columns)
-                    .with_row_groups(20)
-                    // don't need many rows per row group to test metadata parsing
-                    .with_rows_per_row_group(1_000)
-                    .build(),
-            );
-        }
-    }
+    // the benchmark campaign to run (see benchmarks/default.toml)
+    let matrix_path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("benchmarks/default.toml"));
+    println!("Reading benchmark matrix from {matrix_path:?}");
+    let campaign = Matrix::load(&matrix_path).expand();
+    let specs = &campaign.specs;
+    let decoders = &campaign.decoders;
+    let configs = &campaign.configs;
+
+    let output_dir = &campaign.output_dir;
+    fs::create_dir_all(output_dir).unwrap();
 
     println!("Creating parquet files in {:?}", output_dir);
-    for spec in &specs {
-        spec.create().await;
-    }
+    let options = campaign.generation;
+    create_all(specs, options).await;
     println!("Done creating parquet files");
 
+    if campaign.verify_reproducible {
+        let mut failures = 0;
+        for spec in specs {
+            if let Err(reason) = spec.verify_reproducible(&options).await {
+                println!("Not reproducible: {reason}");
+                failures += 1;
+            }
+        }
+        assert_eq!(failures, 0, "{failures} files are not reproducible");
+        println!("All files are reproducible");
+    }
+
     let mut results = vec![];
-    for spec in &specs {
+    for spec in specs {
         let description = spec.to_string();
         println!("running benchmark on {description}");
-        let benchmark = MetadataParseBenchmark::new(description, spec.path().clone());
-        let result = benchmark.run();
+        let benchmark = MetadataParseBenchmark::new(spec)
+            .with_num_runs(campaign.runs)
+            .with_warmup_runs(campaign.warmup_runs)
+            .with_prefetch_hints(campaign.prefetch_hints.clone())
+            .with_metadata_writers(campaign.metadata_writers.clone());
+        let result = benchmark.run(configs);
         println!("{result}");
         results.push(result);
     }
 
     // make a table of results
     let mut table = Table::new();
-    MetadataParseResult::set_headers(&mut table);
+    MetadataParseResult::set_headers(&mut table, configs);
     for result in &results {
         result.add_to_table(&mut table);
     }
     println!("Summary of results:");
     println!("{table}");
 
-    println!("CSV output:");
-    println!("{}", MetadataParseResult::csv_headers().join(",") );
+    if perf_counters::ENABLED {
+        let mut table = Table::new();
+        MetadataParseResult::set_perf_counter_headers(&mut table, configs);
+        for result in &results {
+            result.add_perf_counter_row(&mut table);
+        }
+        println!("Hardware counters per run:");
+        println!("{table}");
+    }
+
+    if decoders
+        .iter()
+        .any(|decoder| matches!(decoder, Decoder::Arrow57Projected(_)))
+    {
+        let mut table = Table::new();
+        MetadataParseResult::set_projection_headers(&mut table, configs);
+        for result in &results {
+            result.add_projection_row(&mut table);
+        }
+        println!(
+            "Re-encoded footers with statistics for a subset of columns compared to all \
+             statistics and to the no stats fork on the same footer, and the no stats fork \
+             compared to Arrow 57:"
+        );
+        println!("{table}");
+    }
+
+    let policies = &campaign.page_index_policies;
+    if policies.contains(&PageIndexPolicy::Both)
+        && (policies.contains(&PageIndexPolicy::ColumnIndex)
+            || policies.contains(&PageIndexPolicy::OffsetIndex))
+    {
+        let mut table = Table::new();
+        MetadataParseResult::set_page_index_headers(&mut table, decoders);
+        for result in &results {
+            result.add_page_index_row(decoders, &mut table);
+        }
+        println!("Page index parse time by page index policy:");
+        println!("{table}");
+    }
+
+    if decoders
+        .iter()
+        .any(|decoder| decoder.push_decoder().is_some())
+    {
+        let mut table = Table::new();
+        MetadataParseResult::set_reader_comparison_headers(&mut table, configs);
+        for result in &results {
+            result.add_reader_comparison_row(&mut table);
+        }
+        println!("Synchronous readers compared to the push decoder (push decoder -> reader):");
+        println!("{table}");
+    }
+
+    let mut table = Table::new();
+    MetadataParseResult::set_writer_comparison_headers(&mut table, configs);
+    for (base_index, other_index) in &campaign.writer_pairs {
+        results[*base_index].add_writer_comparison_row(&results[*other_index], &mut table);
+    }
+    println!("Comparison of files written by different parquet crates or with encryption:");
+    println!("{table}");
+
+    for (title, sweep) in &campaign.row_group_sweeps {
+        let mut table = Table::new();
+        MetadataParseResult::set_scaling_headers(&mut table, configs);
+        for index in sweep {
+            results[*index].add_scaling_row(&mut table);
+        }
+        println!("Scaling with row groups ({title}):");
+        println!("{table}");
+    }
+
+    if !campaign.prefetch_hints.is_empty() {
+        for decoder in decoders
+            .iter()
+            .filter(|decoder| decoder.counts_round_trips())
+        {
+            let mut table = Table::new();
+            MetadataParseResult::set_round_trip_headers(&mut table, &campaign.prefetch_hints);
+            for result in &results {
+                result.add_round_trip_row(*decoder, &campaign.prefetch_hints, &mut table);
+            }
+            println!("Round trips and over-read bytes by prefetch hint ({decoder}):");
+            println!("{table}");
+        }
+    }
+
+    let mut table = Table::new();
+    MetadataParseResult::set_memory_size_headers(&mut table, &distinct_decoders(configs));
     for result in &results {
-        println!("{}", result.to_csv_row().join(",") );
+        result.add_memory_size_row(&mut table);
+    }
+    println!("Heap size of the decoded metadata (amplification vs encoded size):");
+    println!("{table}");
+
+    if !campaign.metadata_writers.is_empty() {
+        let mut table = Table::new();
+        MetadataParseResult::set_metadata_write_headers(&mut table, &campaign.metadata_writers);
+        for result in &results {
+            result.add_metadata_write_row(&mut table);
+        }
+        println!("Metadata write time and encoded size by writer (size in file -> encoded):");
+        println!("{table}");
+    }
+
+    if let Some(object_store) = campaign.object_store {
+        // the parse time of the same reader, `ParquetMetaDataReader`, which also
+        // decrypts, for comparison with the latency
+        let config = DecoderConfig {
+            decoder: Decoder::Arrow57MetadataReader,
+            page_index_policy: PageIndexPolicy::Both,
+        };
+        let mut table = Table::new();
+        ObjectStoreResult::set_headers(&mut table);
+        for (spec, result) in specs.iter().zip(&results) {
+            let object_store_result = ObjectStoreBenchmark::new(spec, object_store)
+                .run(result.parse_duration(config))
+                .await;
+            object_store_result.add_to_table(&mut table);
+        }
+        println!("Loading metadata from a simulated object store ({object_store}):");
+        println!("{table}");
     }
 
+    println!("CSV output:");
+    println!("{}", MetadataParseResult::csv_headers(configs).join(","));
+    for result in &results {
+        println!("{}", result.to_csv_row().join(","));
+    }
 }
@@ -0,0 +1,117 @@
+//! Sidecar manifest that records how a generated parquet file was created
+//!
+//! Generated files are reused across runs, so the manifest is checked before
+//! reusing a file to detect files that were generated from a different spec,
+//! writer or version of the generation code.
+use crate::parquet_file::ParquetFileSpec;
+use crate::writer_options::WriterCrate;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Version of the generation code.
+///
+/// Bump this whenever a change to the generation code changes the files that
+/// are produced, so that existing files are regenerated.
+///
+/// 2: seeded data generation; 3: variable row group sizes; 4: modular encryption;
+/// 5: more nulls in distinct row groups
+pub const GENERATOR_VERSION: u32 = 5;
+
+/// Describes how a generated parquet file was created
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// version of the generation code (see [`GENERATOR_VERSION`])
+    generator_version: u32,
+    /// version of this package
+    package_version: String,
+    /// `created_by` of the parquet writer and the parquet format version
+    writer: String,
+    /// all the parameters of the spec
+    spec: String,
+    /// blake3 hash of the file contents
+    content_hash: String,
+}
+
+impl Manifest {
+    /// Create the manifest for the file described by `spec`, which must already exist
+    pub fn new(spec: &ParquetFileSpec) -> Self {
+        Self {
+            content_hash: content_hash(spec.path()),
+            ..Self::expected(spec)
+        }
+    }
+
+    /// The manifest for `spec`, without the content hash
+    fn expected(spec: &ParquetFileSpec) -> Self {
+        let created_by = match spec.writer_crate() {
+            WriterCrate::Parquet56 => parquet_56::file::properties::DEFAULT_CREATED_BY,
+            WriterCrate::Parquet57 => parquet_57::file::properties::DEFAULT_CREATED_BY,
+        };
+        Self {
+            generator_version: GENERATOR_VERSION,
+            package_version: env!("CARGO_PKG_VERSION").to_string(),
+            writer: format!("{created_by} {}", spec.writer_version()),
+            spec: format!("{spec:?}"),
+            content_hash: String::new(),
+        }
+    }
+
+    /// Returns the path of the manifest for the parquet file at `path`
+    /// (e.g. `foo.parquet.manifest.json`)
+    fn manifest_path(path: &Path) -> PathBuf {
+        let mut manifest_path = path.as_os_str().to_owned();
+        manifest_path.push(".manifest.json");
+        PathBuf::from(manifest_path)
+    }
+
+    /// Writes the manifest next to the file described by `spec`
+    pub fn write(&self, spec: &ParquetFileSpec) {
+        let json = serde_json::to_string_pretty(self).unwrap();
+        fs::write(Self::manifest_path(spec.path()), json).unwrap();
+    }
+
+    /// Checks that the existing file for `spec` was generated from the same spec
+    /// and generation code and has not been modified since.
+    ///
+    /// Returns the reason if the file is stale
+    pub fn check(spec: &ParquetFileSpec) -> Result<(), String> {
+        let manifest_path = Self::manifest_path(spec.path());
+        let Ok(json) = fs::read_to_string(&manifest_path) else {
+            return Err(format!("no manifest at {manifest_path:?}"));
+        };
+        let manifest: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("invalid manifest {manifest_path:?}: {e}"))?;
+
+        let expected = Self::expected(spec);
+        if manifest.generator_version != expected.generator_version {
+            return Err(format!(
+                "generator version {} != {}",
+                manifest.generator_version, expected.generator_version
+            ));
+        }
+        if manifest.package_version != expected.package_version {
+            return Err(format!(
+                "package version {} != {}",
+                manifest.package_version, expected.package_version
+            ));
+        }
+        if manifest.writer != expected.writer {
+            return Err(format!("writer {} != {}", manifest.writer, expected.writer));
+        }
+        if manifest.spec != expected.spec {
+            return Err("spec parameters changed".to_string());
+        }
+        if manifest.content_hash != content_hash(spec.path()) {
+            return Err("content hash mismatch".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Returns the blake3 hash of the contents of the file at `path`, in hex
+pub fn content_hash(path: &Path) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(File::open(path).unwrap()).unwrap();
+    hasher.finalize().to_hex().to_string()
+}
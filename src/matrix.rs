@@ -0,0 +1,411 @@
+//! Declarative benchmark matrix
+//!
+//! A benchmark campaign (the files to generate, the decoders to run and the
+//! number of runs) is described in a TOML or JSON file such as
+//! `benchmarks/default.toml`, so campaigns can be reviewed and reproduced
+//! without editing code.
+//!
+//! Each `[[files]]` group is expanded into one [`ParquetFileSpec`] for every
+//! combination of its `file_types`, `columns` and `row_groups`.
+use crate::benchmark::{Decoder, DecoderConfig, PageIndexPolicy};
+use crate::file_type::FileType;
+use crate::parquet_file::{GenerationOptions, ParquetFileSpec, ParquetFileSpecBuilder};
+use crate::remote::ObjectStoreOptions;
+use crate::writer_options::{
+    Codec, ColumnEncoding, ColumnOverride, ColumnSelection, Encryption, RowGroupSizes, WriterCrate,
+    WriterVersion,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+/// A benchmark campaign, as read from the matrix file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Matrix {
+    /// directory the files are generated in
+    output_dir: PathBuf,
+    /// decoders to benchmark on every file
+    decoders: Vec<Decoder>,
+    /// page indexes loaded by the decoders. Each decoder is run once for each
+    /// policy
+    #[serde(default = "default_page_index_policies")]
+    page_index_policies: Vec<PageIndexPolicy>,
+    /// number of timed runs per decoder (the timings are averaged over them)
+    runs: NonZeroUsize,
+    /// number of untimed warm up runs per decoder
+    warmup_runs: usize,
+    /// generate every file a second time and check the files are byte-identical
+    #[serde(default)]
+    verify_reproducible: bool,
+    /// sizes (in bytes) of the first read from the end of the file, for which the
+    /// round trips and over-read bytes of each push decoder are counted
+    #[serde(default)]
+    prefetch_hints: Vec<u64>,
+    /// parquet crates whose metadata writer is timed encoding the decoded
+    /// metadata and page indexes of every file
+    #[serde(default)]
+    metadata_writers: Vec<WriterCrate>,
+    /// also load the metadata of every file from a simulated object store
+    object_store: Option<ObjectStoreOptions>,
+    /// limits for generating the files (overridden by the `GENERATION_*`
+    /// environment variables)
+    #[serde(default)]
+    generation: GenerationOptions,
+    files: Vec<FileGroup>,
+}
+
+fn default_page_index_policies() -> Vec<PageIndexPolicy> {
+    vec![PageIndexPolicy::Both]
+}
+
+/// A group of files with the same options
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileGroup {
+    /// file name (without `.parquet`). `{file_type}`, `{columns}` and
+    /// `{row_groups}` are replaced with the values for each file
+    name: String,
+    file_types: Vec<FileType>,
+    columns: Vec<usize>,
+    row_groups: Vec<usize>,
+    rows_per_row_group: usize,
+    compression: Option<Vec<Codec>>,
+    encodings: Option<Vec<ColumnEncoding>>,
+    writer_version: Option<WriterVersion>,
+    #[serde(default)]
+    column_overrides: Vec<ColumnOverride>,
+    sorting_columns: Option<ColumnSelection>,
+    encoding_stats: Option<bool>,
+    writer_crate: Option<WriterCrate>,
+    footer_only: Option<bool>,
+    seed: Option<u64>,
+    distinct_row_groups: Option<bool>,
+    row_group_sizes: Option<RowGroupSizes>,
+    trailing_rows: Option<usize>,
+    encryption: Option<Encryption>,
+    /// name of the file (in any group) each file is compared to in the writer
+    /// comparison table, with the same replacements as `name`
+    compare_to: Option<String>,
+    /// print a table of parse time against row groups for each file type and
+    /// column count
+    #[serde(default)]
+    row_group_scaling: bool,
+}
+
+/// The specs and reports of a [`Matrix`]
+#[derive(Debug)]
+pub struct Campaign {
+    pub output_dir: PathBuf,
+    pub decoders: Vec<Decoder>,
+    pub page_index_policies: Vec<PageIndexPolicy>,
+    /// every combination of `decoders` and `page_index_policies` the decoder
+    /// supports
+    pub configs: Vec<DecoderConfig>,
+    pub runs: usize,
+    pub warmup_runs: usize,
+    pub verify_reproducible: bool,
+    pub prefetch_hints: Vec<u64>,
+    pub metadata_writers: Vec<WriterCrate>,
+    pub object_store: Option<ObjectStoreOptions>,
+    pub generation: GenerationOptions,
+    pub specs: Vec<ParquetFileSpec>,
+    /// Pairs of (base, other) spec indexes for the writer comparison table
+    pub writer_pairs: Vec<(usize, usize)>,
+    /// (title, spec indexes) for each row group scaling table
+    pub row_group_sweeps: Vec<(String, Vec<usize>)>,
+}
+
+impl Matrix {
+    /// Reads the matrix from a `.json` or `.toml` file
+    pub fn load(path: &Path) -> Self {
+        let contents = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("can not read benchmark matrix {path:?}: {e}"));
+        let matrix = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            serde_json::from_str(&contents).map_err(|e| e.to_string())
+        } else {
+            toml::from_str(&contents).map_err(|e| e.to_string())
+        };
+        matrix.unwrap_or_else(|e| panic!("invalid benchmark matrix {path:?}: {e}"))
+    }
+
+    /// Expands the file groups into specs
+    pub fn expand(self) -> Campaign {
+        let mut specs = vec![];
+        let mut names = HashMap::new();
+        // (spec index, name of the file to compare to)
+        let mut comparisons = vec![];
+        let mut row_group_sweeps = vec![];
+        for group in &self.files {
+            for file_type in &group.file_types {
+                for columns in &group.columns {
+                    let mut sweep = vec![];
+                    for row_groups in &group.row_groups {
+                        let replace = |template: &str| {
+                            template
+                                .replace("{file_type}", &file_type.to_string())
+                                .replace("{columns}", &columns.to_string())
+                                .replace("{row_groups}", &row_groups.to_string())
+                        };
+                        let name = replace(&group.name);
+                        let path = self.output_dir.join(format!("{name}.parquet"));
+                        let spec = group.spec(path, *file_type, *columns, *row_groups);
+                        if names.insert(name.clone(), specs.len()).is_some() {
+                            panic!("duplicate file name {name} in benchmark matrix");
+                        }
+                        if let Some(compare_to) = &group.compare_to {
+                            comparisons.push((specs.len(), replace(compare_to)));
+                        }
+                        sweep.push(specs.len());
+                        specs.push(spec);
+                    }
+                    if group.row_group_scaling {
+                        let data = if group.footer_only == Some(true) {
+                            "footer only"
+                        } else {
+                            "data"
+                        };
+                        row_group_sweeps
+                            .push((format!("{file_type} {columns} cols, {data}"), sweep));
+                    }
+                }
+            }
+        }
+
+        let writer_pairs = comparisons
+            .into_iter()
+            .map(|(index, compare_to)| {
+                let Some(base_index) = names.get(&compare_to) else {
+                    panic!("compare_to {compare_to} does not match any file in benchmark matrix");
+                };
+                (*base_index, index)
+            })
+            .collect();
+
+        // projected footers are compared to the same footer re-encoded with all
+        // the statistics, so the timings only differ by the removed statistics,
+        // and to the "no stats" fork on that footer, the best case
+        let mut decoders = self.decoders;
+        let projected = |decoder: &Decoder| matches!(decoder, Decoder::Arrow57Projected(_));
+        if let Some(first_projected) = decoders.iter().position(projected) {
+            for baseline in [
+                Decoder::Arrow57NoStatsReencoded,
+                Decoder::Arrow57Projected(100),
+            ] {
+                if !decoders.contains(&baseline) {
+                    decoders.insert(first_projected, baseline);
+                }
+            }
+        }
+
+        let configs = decoders
+            .iter()
+            .flat_map(|decoder| {
+                self.page_index_policies
+                    .iter()
+                    .map(|page_index_policy| DecoderConfig {
+                        decoder: *decoder,
+                        page_index_policy: *page_index_policy,
+                    })
+            })
+            .filter(|config| config.decoder.supports(config.page_index_policy))
+            .collect();
+
+        Campaign {
+            output_dir: self.output_dir,
+            decoders,
+            page_index_policies: self.page_index_policies,
+            configs,
+            runs: self.runs.get(),
+            warmup_runs: self.warmup_runs,
+            verify_reproducible: self.verify_reproducible,
+            prefetch_hints: self.prefetch_hints,
+            metadata_writers: self.metadata_writers,
+            object_store: self.object_store,
+            generation: self.generation.with_env_overrides(),
+            specs,
+            writer_pairs,
+            row_group_sweeps,
+        }
+    }
+}
+
+impl FileGroup {
+    fn spec(
+        &self,
+        path: PathBuf,
+        file_type: FileType,
+        columns: usize,
+        row_groups: usize,
+    ) -> ParquetFileSpec {
+        let mut builder = ParquetFileSpecBuilder::new()
+            .with_path(path)
+            .with_file_type(file_type)
+            .with_columns(columns)
+            .with_row_groups(row_groups)
+            .with_rows_per_row_group(self.rows_per_row_group);
+        if let Some(compression) = &self.compression {
+            builder = builder.with_compression(compression.clone());
+        }
+        if let Some(encodings) = &self.encodings {
+            builder = builder.with_encodings(encodings.clone());
+        }
+        if let Some(writer_version) = self.writer_version {
+            builder = builder.with_writer_version(writer_version);
+        }
+        for column_override in &self.column_overrides {
+            builder = builder.with_column_override(column_override.clone());
+        }
+        if let Some(sorting_columns) = &self.sorting_columns {
+            builder = builder.with_sorting_columns(sorting_columns.clone());
+        }
+        if let Some(encoding_stats) = self.encoding_stats {
+            builder = builder.with_encoding_stats(encoding_stats);
+        }
+        if let Some(writer_crate) = self.writer_crate {
+            builder = builder.with_writer_crate(writer_crate);
+        }
+        if let Some(footer_only) = self.footer_only {
+            builder = builder.with_footer_only(footer_only);
+        }
+        if let Some(seed) = self.seed {
+            builder = builder.with_seed(seed);
+        }
+        if let Some(distinct_row_groups) = self.distinct_row_groups {
+            builder = builder.with_distinct_row_groups(distinct_row_groups);
+        }
+        if let Some(row_group_sizes) = self.row_group_sizes {
+            builder = builder.with_row_group_sizes(row_group_sizes);
+        }
+        if let Some(trailing_rows) = self.trailing_rows {
+            builder = builder.with_trailing_rows(trailing_rows);
+        }
+        if let Some(encryption) = self.encryption {
+            builder = builder.with_encryption(encryption);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(toml: &str) -> Result<Matrix, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    const MATRIX: &str = r#"
+        output_dir = "out"
+        decoders = ["arrow57", { arrow57_projected = 10 }]
+        runs = 1
+        warmup_runs = 0
+
+        [[files]]
+        name = "{file_type}_{columns}_cols_{row_groups}_rgs"
+        file_types = ["Float", "String"]
+        columns = [10, 20]
+        row_groups = [1, 2]
+        rows_per_row_group = 100
+        row_group_scaling = true
+
+        [[files]]
+        name = "{file_type}_{columns}_cols_parquet57"
+        file_types = ["Float"]
+        columns = [10]
+        row_groups = [1]
+        rows_per_row_group = 100
+        writer_crate = "parquet57"
+        compare_to = "{file_type}_{columns}_cols_{row_groups}_rgs"
+    "#;
+
+    #[test]
+    fn expands_every_combination() {
+        let campaign = matrix(MATRIX).unwrap().expand();
+        let paths: Vec<_> = campaign
+            .specs
+            .iter()
+            .map(|spec| spec.path().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "out/Float32_10_cols_1_rgs.parquet",
+                "out/Float32_10_cols_2_rgs.parquet",
+                "out/Float32_20_cols_1_rgs.parquet",
+                "out/Float32_20_cols_2_rgs.parquet",
+                "out/String_10_cols_1_rgs.parquet",
+                "out/String_10_cols_2_rgs.parquet",
+                "out/String_20_cols_1_rgs.parquet",
+                "out/String_20_cols_2_rgs.parquet",
+                "out/Float32_10_cols_parquet57.parquet",
+            ]
+        );
+        // compare_to names a file in another group
+        assert_eq!(campaign.writer_pairs, vec![(0, 8)]);
+        let sweeps: Vec<_> = campaign
+            .row_group_sweeps
+            .iter()
+            .map(|(title, sweep)| (title.as_str(), sweep.clone()))
+            .collect();
+        assert_eq!(
+            sweeps,
+            vec![
+                ("Float32 10 cols, data", vec![0, 1]),
+                ("Float32 20 cols, data", vec![2, 3]),
+                ("String 10 cols, data", vec![4, 5]),
+                ("String 20 cols, data", vec![6, 7]),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_projection_baseline() {
+        let campaign = matrix(MATRIX).unwrap().expand();
+        assert_eq!(
+            campaign.decoders,
+            vec![
+                Decoder::Arrow57,
+                Decoder::Arrow57Projected(100),
+                Decoder::Arrow57NoStatsReencoded,
+                Decoder::Arrow57Projected(10),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "compare_to Float32_99_cols does not match")]
+    fn rejects_unknown_compare_to() {
+        let toml = MATRIX.replace(
+            "compare_to = \"{file_type}_{columns}_cols_{row_groups}_rgs\"",
+            "compare_to = \"{file_type}_99_cols\"",
+        );
+        matrix(&toml).unwrap().expand();
+    }
+
+    #[test]
+    fn runs_must_not_be_zero() {
+        assert!(matrix(&MATRIX.replace("runs = 1", "runs = 0")).is_err());
+        assert!(matrix(MATRIX).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let unknown_top_level = MATRIX.replace("runs = 1", "runs = 1\nrun = 2");
+        let error = matrix(&unknown_top_level).unwrap_err().to_string();
+        assert!(error.contains("unknown field `run`"), "{error}");
+
+        let unknown_in_group = MATRIX.replace("row_group_scaling = true", "row_group_scale = true");
+        let error = matrix(&unknown_in_group).unwrap_err().to_string();
+        assert!(error.contains("unknown field `row_group_scale`"), "{error}");
+
+        let unknown_generation = format!("generation = {{ threads = 2 }}\n{MATRIX}");
+        let error = matrix(&unknown_generation).unwrap_err().to_string();
+        assert!(error.contains("unknown field `threads`"), "{error}");
+    }
+}
@@ -0,0 +1,20 @@
+//! Memory usage reporting
+
+/// Returns the peak resident set size of this process in bytes.
+///
+/// This is read from `VmHWM` in `/proc/self/status`, so it is only available on Linux
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    // line looks like "VmHWM:    123456 kB"
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Formats the peak resident set size for progress messages
+pub fn format_peak_rss() -> String {
+    match peak_rss_bytes() {
+        Some(bytes) => format!("peak RSS {:.02} MB", bytes as f64 / (1024.0 * 1024.0)),
+        None => "peak RSS unavailable".to_string(),
+    }
+}
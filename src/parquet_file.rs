@@ -1,15 +1,41 @@
 use crate::file_type::FileType;
-use arrow::array::{ArrayRef, RecordBatch};
-use arrow::datatypes::SchemaRef;
-use parquet_56::arrow::ArrowWriter;
-use parquet_56::arrow::arrow_writer::{ArrowColumnChunk, ArrowColumnWriter, compute_leaves};
-use parquet_56::file::properties::WriterProperties;
+use crate::manifest::{Manifest, content_hash};
+use crate::memory::format_peak_rss;
+use crate::synthetic;
+use crate::validation::validate;
+use crate::writer_options::{
+    Codec, ColumnEncoding, ColumnOverride, ColumnSelection, Encryption, RowGroupSizes,
+    StatisticsLevel, WriterCrate, WriterVersion, display_list,
+};
+use arrow::array::RecordBatch;
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
 use std::fmt::Display;
 use std::fs;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
+use tempfile::NamedTempFile;
+use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 
+/// Maximum number of rows in the batch that is written repeatedly to fill each
+/// row group
+const BATCH_ROWS: usize = 100;
+
+/// With distinct row groups, the cached batch has this many times [`BATCH_ROWS`]
+/// rows, and each row group uses a different window of it for each column
+const DISTINCT_BATCH_FACTOR: usize = 4;
+
+/// Fraction of the generated values that are null
+const NULL_DENSITY: f32 = 0.0001;
+
+/// Fraction of null values with distinct row groups, high enough that the
+/// windows used for each row group have different null counts
+const DISTINCT_NULL_DENSITY: f32 = 0.05;
+
 /// Builder for a ParquetFileSpec
 #[derive(Debug, Default)]
 pub struct ParquetFileSpecBuilder {
@@ -18,6 +44,19 @@ pub struct ParquetFileSpecBuilder {
     columns: Option<usize>,
     row_groups: Option<usize>,
     rows_per_row_group: Option<usize>,
+    compression: Option<Vec<Codec>>,
+    encodings: Option<Vec<ColumnEncoding>>,
+    writer_version: Option<WriterVersion>,
+    column_overrides: Vec<ColumnOverride>,
+    sorting_columns: Option<ColumnSelection>,
+    encoding_stats: Option<bool>,
+    writer_crate: Option<WriterCrate>,
+    footer_only: Option<bool>,
+    seed: Option<u64>,
+    distinct_row_groups: Option<bool>,
+    row_group_sizes: Option<RowGroupSizes>,
+    trailing_rows: Option<usize>,
+    encryption: Option<Encryption>,
 }
 
 impl ParquetFileSpecBuilder {
@@ -44,6 +83,78 @@ impl ParquetFileSpecBuilder {
         self.rows_per_row_group = Some(rows_per_row_group);
         self
     }
+    /// Compression codecs, assigned to columns round-robin (defaults to uncompressed)
+    pub fn with_compression(mut self, compression: Vec<Codec>) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+    /// Column encodings, assigned to columns round-robin (defaults to dictionary)
+    pub fn with_encodings(mut self, encodings: Vec<ColumnEncoding>) -> Self {
+        self.encodings = Some(encodings);
+        self
+    }
+    /// Parquet writer version, which selects data page v1 or v2 (defaults to PARQUET_1_0)
+    pub fn with_writer_version(mut self, writer_version: WriterVersion) -> Self {
+        self.writer_version = Some(writer_version);
+        self
+    }
+    /// Add per-column writer property overrides, applied in the order they are added
+    /// (so later overrides take precedence over earlier ones)
+    pub fn with_column_override(mut self, column_override: ColumnOverride) -> Self {
+        self.column_overrides.push(column_override);
+        self
+    }
+    /// Declare `sorting_columns` (ascending, nulls first) on each row group for the
+    /// selected columns. Only the metadata is written, the data is not sorted.
+    pub fn with_sorting_columns(mut self, sorting_columns: ColumnSelection) -> Self {
+        self.sorting_columns = Some(sorting_columns);
+        self
+    }
+    /// Write `encoding_stats` for each column chunk (defaults to true)
+    pub fn with_encoding_stats(mut self, encoding_stats: bool) -> Self {
+        self.encoding_stats = Some(encoding_stats);
+        self
+    }
+    /// Version of the parquet crate used to write the file (defaults to parquet 56)
+    pub fn with_writer_crate(mut self, writer_crate: WriterCrate) -> Self {
+        self.writer_crate = Some(writer_crate);
+        self
+    }
+    /// Write only the footer and page indexes, with a placeholder data region
+    /// instead of encoded data pages (defaults to false). See [`crate::synthetic`]
+    pub fn with_footer_only(mut self, footer_only: bool) -> Self {
+        self.footer_only = Some(footer_only);
+        self
+    }
+    /// Seed for the generated data. Files generated from the same spec and seed
+    /// are byte-identical
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    /// Use different data in each row group, so the statistics and page indexes
+    /// of each row group differ
+    pub fn with_distinct_row_groups(mut self, distinct_row_groups: bool) -> Self {
+        self.distinct_row_groups = Some(distinct_row_groups);
+        self
+    }
+    pub fn with_row_group_sizes(mut self, row_group_sizes: RowGroupSizes) -> Self {
+        self.row_group_sizes = Some(row_group_sizes);
+        self
+    }
+    /// Make the last row group a partial row group with `trailing_rows` rows, fewer
+    /// than `rows_per_row_group`, in place of a full one (needs at least 2 row
+    /// groups)
+    pub fn with_trailing_rows(mut self, trailing_rows: usize) -> Self {
+        self.trailing_rows = Some(trailing_rows);
+        self
+    }
+    /// Encrypt the file with the test keys in [`crate::encryption`] (defaults to
+    /// not encrypted)
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
     pub fn build(self) -> ParquetFileSpec {
         let Self {
             path,
@@ -51,26 +162,132 @@ impl ParquetFileSpecBuilder {
             columns,
             row_groups,
             rows_per_row_group,
+            compression,
+            encodings,
+            writer_version,
+            column_overrides,
+            sorting_columns,
+            encoding_stats,
+            writer_crate,
+            footer_only,
+            seed,
+            distinct_row_groups,
+            row_group_sizes,
+            trailing_rows,
+            encryption,
         } = self;
 
+        let file_type = file_type.expect("file_type is required");
+        let compression = compression.unwrap_or_else(|| vec![Codec::Uncompressed]);
+        assert!(!compression.is_empty(), "compression must not be empty");
+        let encodings = encodings.unwrap_or_else(|| vec![ColumnEncoding::Dictionary]);
+        assert!(!encodings.is_empty(), "encodings must not be empty");
+        let override_encodings = column_overrides
+            .iter()
+            .filter_map(|column_override| column_override.encoding());
+        for encoding in encodings.iter().copied().chain(override_encodings) {
+            assert!(
+                file_type.supports_encoding(encoding),
+                "{encoding} encoding is not supported for {file_type} columns"
+            );
+        }
+        let row_group_sizes = row_group_sizes.unwrap_or_default();
+        match row_group_sizes {
+            RowGroupSizes::Fixed => {}
+            RowGroupSizes::RandomRange { min, max } => assert!(
+                0 < min && min <= max,
+                "invalid random row group size range {min}-{max}"
+            ),
+            // the row groups shrink, a larger ratio would grow them without bound
+            RowGroupSizes::Geometric { ratio } => assert!(
+                ratio > 0.0 && ratio <= 1.0,
+                "invalid geometric row group size ratio {ratio}"
+            ),
+        }
+        let row_groups = row_groups.expect("row_groups is required");
+        let rows_per_row_group = rows_per_row_group.expect("rows_per_row_group is required");
+        assert_ne!(rows_per_row_group, 0, "rows_per_row_group must not be 0");
+        if let Some(trailing_rows) = trailing_rows {
+            assert!(
+                0 < trailing_rows && trailing_rows < rows_per_row_group,
+                "trailing_rows must be between 1 and {} rows",
+                rows_per_row_group - 1
+            );
+            assert!(
+                row_groups > 1,
+                "trailing_rows needs at least one other row group"
+            );
+        }
+        let writer_crate = writer_crate.unwrap_or_default();
+        let footer_only = footer_only.unwrap_or(false);
+        assert!(
+            !footer_only || writer_crate == WriterCrate::Parquet56,
+            "footer only files are only supported for the parquet 56 writer"
+        );
+        let encryption = encryption.unwrap_or_default();
+        let encoding_stats = encoding_stats.unwrap_or(true);
+        assert!(
+            encryption == Encryption::None || (!footer_only && encoding_stats),
+            "encrypted files can not be footer only or have their encoding stats removed"
+        );
+
         ParquetFileSpec {
             path: path.expect("path is required"),
-            file_type: file_type.expect("file_type is required"),
+            file_type,
             columns: columns.expect("columns is required"),
-            row_groups: row_groups.expect("row_groups is required"),
-            rows_per_row_group: rows_per_row_group.expect("rows_per_row_group is required"),
+            row_groups,
+            rows_per_row_group,
+            compression,
+            encodings,
+            writer_version: writer_version.unwrap_or_default(),
+            column_overrides,
+            sorting_columns,
+            encoding_stats,
+            writer_crate,
+            footer_only,
+            seed: seed.unwrap_or(0),
+            distinct_row_groups: distinct_row_groups.unwrap_or(false),
+            row_group_sizes,
+            trailing_rows,
+            encryption,
         }
     }
 }
 
 /// Creates a parquet files with specified characteristics
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParquetFileSpec {
     path: PathBuf,
     file_type: FileType,
     columns: usize,
     row_groups: usize,
     rows_per_row_group: usize,
+    /// compression codec for column `i` is `compression[i % compression.len()]`
+    compression: Vec<Codec>,
+    /// encoding for column `i` is `encodings[i % encodings.len()]`
+    encodings: Vec<ColumnEncoding>,
+    writer_version: WriterVersion,
+    /// per-column writer property overrides, applied in order
+    column_overrides: Vec<ColumnOverride>,
+    /// columns declared in the `sorting_columns` of each row group
+    sorting_columns: Option<ColumnSelection>,
+    /// whether to keep the `encoding_stats` of each column chunk
+    encoding_stats: bool,
+    /// version of the parquet crate used to write the file
+    writer_crate: WriterCrate,
+    /// write only the footer and page indexes, without real data pages
+    footer_only: bool,
+    /// seed for the random data (and statistics of footer only files)
+    seed: u64,
+    /// use different data in each row group, derived from a per row group seed.
+    /// (the statistics of footer only files always differ between row groups)
+    distinct_row_groups: bool,
+    /// distribution of the number of rows in each row group
+    row_group_sizes: RowGroupSizes,
+    /// number of rows in the last row group, if it is a partial row group
+    trailing_rows: Option<usize>,
+    /// parquet modular encryption of the file
+    encryption: Encryption,
 }
 
 impl Display for ParquetFileSpec {
@@ -79,7 +296,45 @@ impl Display for ParquetFileSpec {
             f,
             " {:?} {} cols {} row groups",
             self.file_type, self.columns, self.row_groups
-        )
+        )?;
+        // only mention non default options to keep descriptions short
+        if self.compression != [Codec::Uncompressed] {
+            write!(f, " {}", display_list(&self.compression))?;
+        }
+        if self.encodings != [ColumnEncoding::Dictionary] {
+            write!(f, " {}", display_list(&self.encodings))?;
+        }
+        for column_override in &self.column_overrides {
+            write!(f, " ({column_override})")?;
+        }
+        if let Some(sorting_columns) = &self.sorting_columns {
+            write!(f, " (sorted by {sorting_columns})")?;
+        }
+        if !self.encoding_stats {
+            write!(f, " no encoding stats")?;
+        }
+        if self.writer_crate != WriterCrate::default() {
+            write!(f, " ({} writer)", self.writer_crate)?;
+        }
+        if self.footer_only {
+            write!(f, " footer only")?;
+        }
+        if self.seed != 0 {
+            write!(f, " seed {}", self.seed)?;
+        }
+        if self.distinct_row_groups {
+            write!(f, " distinct row groups")?;
+        }
+        if self.row_group_sizes != RowGroupSizes::Fixed {
+            write!(f, " {}", self.row_group_sizes)?;
+        }
+        if let Some(trailing_rows) = self.trailing_rows {
+            write!(f, " trailing row group of {trailing_rows} rows")?;
+        }
+        if self.encryption != Encryption::None {
+            write!(f, " ({})", self.encryption)?;
+        }
+        Ok(())
     }
 }
 
@@ -88,144 +343,963 @@ impl ParquetFileSpec {
         &self.path
     }
 
-    pub async fn create(&self) {
+    pub fn writer_version(&self) -> WriterVersion {
+        self.writer_version
+    }
+
+    pub fn writer_crate(&self) -> WriterCrate {
+        self.writer_crate
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn row_groups(&self) -> usize {
+        self.row_groups
+    }
+
+    /// Number of rows in the row group at `row_group`, following the
+    /// `row_group_sizes` distribution and `trailing_rows`
+    pub fn row_group_num_rows(&self, row_group: usize) -> usize {
+        if let Some(trailing_rows) = self.trailing_rows
+            && row_group + 1 == self.row_groups
+        {
+            return trailing_rows;
+        }
+        match self.row_group_sizes {
+            RowGroupSizes::Fixed => self.rows_per_row_group,
+            RowGroupSizes::RandomRange { min, max } => {
+                // offset the seed so the sizes do not correlate with the data
+                let seed = self.row_group_seed(row_group).wrapping_add(1);
+                StdRng::seed_from_u64(seed).random_range(min..=max)
+            }
+            RowGroupSizes::Geometric { ratio } => {
+                let rows = self.rows_per_row_group as f64 * ratio.powi(row_group as i32);
+                (rows.round() as usize).max(1)
+            }
+        }
+    }
+
+    /// Largest number of rows in any row group
+    fn max_row_group_num_rows(&self) -> usize {
+        (0..self.row_groups)
+            .map(|row_group| self.row_group_num_rows(row_group))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Compression codec of the column at `column_index`: the `compression`
+    /// codecs round-robin, unless a column override sets one
+    pub fn codec(&self, column_index: usize) -> Codec {
+        self.column_overrides
+            .iter()
+            .filter(|column_override| column_override.applies_to(column_index))
+            .filter_map(|column_override| column_override.compression())
+            .next_back()
+            .unwrap_or(self.compression[column_index % self.compression.len()])
+    }
+
+    /// Encoding of the column at `column_index`: the `encodings` round-robin,
+    /// unless a column override sets one
+    pub fn encoding(&self, column_index: usize) -> ColumnEncoding {
+        self.column_overrides
+            .iter()
+            .filter(|column_override| column_override.applies_to(column_index))
+            .filter_map(|column_override| column_override.encoding())
+            .next_back()
+            .unwrap_or(self.encodings[column_index % self.encodings.len()])
+    }
+
+    /// Statistics written for the column at `column_index`, after applying the
+    /// column overrides (the writer default is page level statistics)
+    pub fn statistics_level(&self, column_index: usize) -> StatisticsLevel {
+        self.column_overrides
+            .iter()
+            .filter(|column_override| column_override.applies_to(column_index))
+            .filter_map(|column_override| column_override.statistics())
+            .next_back()
+            .unwrap_or(StatisticsLevel::Page)
+    }
+
+    /// True if the column at `column_index` is dictionary encoded, after applying
+    /// the column overrides (which footer only files do not write)
+    pub fn dictionary_enabled(&self, column_index: usize) -> bool {
+        let dictionary = self.encoding(column_index) == ColumnEncoding::Dictionary;
+        if self.footer_only {
+            return dictionary;
+        }
+        self.column_overrides
+            .iter()
+            .filter(|column_override| column_override.applies_to(column_index))
+            .filter_map(|column_override| column_override.dictionary())
+            .next_back()
+            .unwrap_or(dictionary)
+    }
+
+    /// True if a bloom filter is written for the column at `column_index` (never
+    /// for footer only files)
+    pub fn bloom_filter(&self, column_index: usize) -> bool {
+        !self.footer_only
+            && self
+                .column_overrides
+                .iter()
+                .filter(|column_override| column_override.applies_to(column_index))
+                .filter_map(|column_override| column_override.bloom_filter())
+                .next_back()
+                .unwrap_or(false)
+    }
+
+    pub fn sorting_columns(&self) -> Option<&ColumnSelection> {
+        self.sorting_columns.as_ref()
+    }
+
+    pub fn encoding_stats(&self) -> bool {
+        self.encoding_stats
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn encryption(&self) -> Encryption {
+        self.encryption
+    }
+
+    /// Names of the columns in the file, which are needed for the column keys
+    /// of encrypted files
+    pub fn column_names(&self) -> Vec<String> {
+        let schema = self.file_type.schema(self.columns);
+        schema
+            .fields()
+            .iter()
+            .map(|field| field.name().to_string())
+            .collect()
+    }
+
+    /// Seed for the data of row group `row_group`, derived from the spec's seed
+    fn row_group_seed(&self, row_group: usize) -> u64 {
+        self.seed ^ (row_group as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    /// Offset of the window of the cached batch used for each column of
+    /// `row_group`, between 0 and `max_offset` (inclusive)
+    fn row_group_offsets(&self, row_group: usize, max_offset: usize) -> Vec<usize> {
+        let mut rng = StdRng::seed_from_u64(self.row_group_seed(row_group));
+        (0..self.columns)
+            .map(|_| rng.random_range(0..=max_offset))
+            .collect()
+    }
+
+    /// Short label used to prefix progress messages (the file name)
+    pub fn label(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.to_string())
+    }
+
+    /// Rough estimate of the memory needed to generate this file with `options`, in MB.
+    ///
+    /// Generation keeps the encoded column chunks of one row group (or one group of
+    /// columns) in memory, as well as the state of the column writers of the row
+    /// group, which are all created at its start
+    pub fn estimated_memory_mb(&self, options: &GenerationOptions) -> usize {
+        let column_writer_overhead = 16 * 1024;
+        if self.footer_only {
+            // the decoded metadata is kept in memory, roughly 1KB per column chunk
+            return self.columns * self.row_groups / 1024;
+        }
+        let value_size = self.file_type.estimated_value_size();
+        let encoded_column_size = self.max_row_group_num_rows() * value_size;
+        let columns_in_flight = options
+            .column_group_size
+            .map_or(self.columns, |group_size| group_size.min(self.columns));
+        // x2 for the encoded pages and the writer buffers
+        let mut bytes =
+            columns_in_flight * 2 * encoded_column_size + self.columns * column_writer_overhead;
+        if self.distinct_row_groups {
+            // the larger cached batch the row groups are sliced from
+            bytes += self.columns * BATCH_ROWS * DISTINCT_BATCH_FACTOR * value_size;
+        }
+        bytes / (1024 * 1024)
+    }
+
+    pub async fn create(&self, options: &GenerationOptions) {
         if fs::exists(&self.path).unwrap() {
-            println!("File {:?} already exists, skipping", self.path);
-            return;
+            match Manifest::check(self) {
+                Ok(()) => {
+                    println!(
+                        "[{}] File {:?} already exists, skipping",
+                        self.label(),
+                        self.path
+                    );
+                    return;
+                }
+                Err(reason) => println!(
+                    "[{}] File {:?} is stale ({reason}), regenerating",
+                    self.label(),
+                    self.path
+                ),
+            }
         };
 
         // Implementation to create a parquet file based on the spec
-        println!("Creating a {self:#?}");
+        println!("[{}] Creating a {self:#?}", self.label());
+        let start = Instant::now();
 
-        let mut temp_file = tempfile::NamedTempFile::new().unwrap();
-        let schema = self.file_type.schema(self.columns);
-        let props = WriterProperties::builder()
-            .set_max_row_group_size(self.rows_per_row_group)
-            .build();
-        let writer = ArrowWriter::try_new(&mut temp_file, schema, Some(props)).unwrap();
-
-        let (mut file_writer, row_group_factory) = writer.into_serialized_writer().unwrap();
-
-        let mut rows_written = 0;
-        for rg in 0..self.row_groups {
-            let col_writers = row_group_factory.create_column_writers(rg).unwrap();
-            let row_group_encoder =
-                RowGroupEncoder::new(self.file_type.schema(self.columns), col_writers);
-
-            let encoded_columns = encode_row_group(
-                self.file_type,
-                self.columns,
-                self.rows_per_row_group,
-                row_group_encoder,
-            )
-            .await;
-            let mut rg_writer = file_writer.next_row_group().unwrap();
-            for col_chunk in encoded_columns.into_iter() {
-                col_chunk.append_to_row_group(&mut rg_writer).unwrap();
+        let temp_file = self.write_temp_file(options).await;
+        if let Err(e) = validate(self, temp_file.path()) {
+            panic!("[{}] generated file is invalid: {e}", self.label());
+        }
+        // rename the temp file to the final path
+        temp_file.persist(&self.path).unwrap();
+        // write the manifest last, so an interrupted run leaves a stale file
+        Manifest::new(self).write(self);
+        println!(
+            "[{}] Done in {:?}, {}",
+            self.label(),
+            start.elapsed(),
+            format_peak_rss()
+        );
+    }
+
+    /// Writes the file described by this spec to a new temporary file
+    async fn write_temp_file(&self, options: &GenerationOptions) -> NamedTempFile {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        if self.footer_only {
+            synthetic::write_footer_only(self, temp_file.as_file_mut());
+        } else {
+            match self.writer_crate {
+                WriterCrate::Parquet56 => v56::write(self, options, temp_file.as_file_mut()).await,
+                WriterCrate::Parquet57 => v57::write(self, options, temp_file.as_file_mut()).await,
             }
-            let rg_metadata = rg_writer.close().unwrap();
-            let mb_size = rg_metadata.total_byte_size() as f64 / (1024.0 * 1024.0);
+        }
+        temp_file
+    }
+
+    /// Checks that generating this spec again produces a file that is
+    /// byte-identical to the existing file (which must already be created).
+    ///
+    /// Returns the content hashes if the files differ
+    pub async fn verify_reproducible(&self, options: &GenerationOptions) -> Result<(), String> {
+        if self.encryption != Encryption::None {
+            // the encryption nonces are random
             println!(
-                "Completed row group of {} columns, {} rows {mb_size:.02} MB",
-                rg_metadata.num_columns(),
-                rg_metadata.num_rows(),
+                "[{}] Skipping reproducibility check of encrypted file",
+                self.label()
             );
-            rows_written += rg_metadata.num_rows();
+            return Ok(());
+        }
+        println!("[{}] Verifying the file is reproducible", self.label());
+        let temp_file = self.write_temp_file(options).await;
+        let expected = content_hash(&self.path);
+        let actual = content_hash(temp_file.path());
+        if expected != actual {
+            return Err(format!(
+                "{:?} has content hash {expected} but regenerating it produced {actual}",
+                self.path
+            ));
         }
-        file_writer.close().unwrap();
-        println!("Wrote {rows_written} rows to {:?}", self.path);
+        Ok(())
+    }
+}
 
-        // rename the temp file to the final path
-        temp_file.persist(&self.path).unwrap();
+/// Options for generating several files concurrently, set in the `[generation]`
+/// table of the matrix file
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenerationOptions {
+    /// Maximum number of files generated at the same time
+    concurrency: usize,
+    /// Approximate memory budget for all files being generated, in MB (see
+    /// [`ParquetFileSpec::estimated_memory_mb`])
+    memory_budget_mb: usize,
+    /// If set, encode the columns of each row group in groups of at most this many
+    /// columns, appending each group to the file before starting the next. This
+    /// bounds the number of encoding tasks and encoded column chunks in memory,
+    /// which matters for very wide files. The files written are the same.
+    column_group_size: Option<usize>,
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self {
+            // each file already encodes its columns in parallel
+            concurrency: 2,
+            memory_budget_mb: 8 * 1024,
+            column_group_size: None,
+        }
     }
 }
 
-async fn encode_row_group(
-    file_type: FileType,
-    columns: usize,
-    rows_per_row_group: usize,
-    mut row_group_encoder: RowGroupEncoder,
-) -> Vec<ArrowColumnChunk> {
-    let num_rows = rows_per_row_group.min(100);
-    // use the same batch repeatedly otherwise the data generation will dominate the time
-    let batch = file_type.create_batch(0, num_rows, columns);
-    let mut rows_written = 0;
-    while rows_written < rows_per_row_group {
-        let rows_left = rows_per_row_group - rows_written;
-        let batch = if rows_left < batch.num_rows() {
-            batch.slice(0, rows_left)
-        } else {
-            batch.clone()
-        };
-        // todo handle the case where rows is not a multiple of batch size
-        row_group_encoder.encode_batch(&batch).await;
-        rows_written += batch.num_rows();
+impl GenerationOptions {
+    /// Overrides the options with the `GENERATION_CONCURRENCY`,
+    /// `GENERATION_MEMORY_BUDGET_MB` and `GENERATION_COLUMN_GROUP_SIZE` environment
+    /// variables, keeping the current value of any that are not set
+    pub fn with_env_overrides(self) -> Self {
+        Self {
+            concurrency: env_or("GENERATION_CONCURRENCY", self.concurrency),
+            memory_budget_mb: env_or("GENERATION_MEMORY_BUDGET_MB", self.memory_budget_mb),
+            // 0 encodes all the columns of a row group at once
+            column_group_size: Some(env_or(
+                "GENERATION_COLUMN_GROUP_SIZE",
+                self.column_group_size.unwrap_or(0),
+            ))
+            .filter(|size| *size > 0),
+        }
+    }
+}
+
+/// Returns the value of the environment variable `name`, or `default` if not set
+fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid value for {name}: {value}")),
+        Err(_) => default,
+    }
+}
+
+/// Creates the files for all `specs`, generating several files concurrently
+/// within the limits of `options`
+pub async fn create_all(specs: &[ParquetFileSpec], options: GenerationOptions) {
+    println!("Generating {} files with {options:?}", specs.len());
+    let concurrency = Arc::new(Semaphore::new(options.concurrency.max(1)));
+    let memory_budget_mb = options.memory_budget_mb.max(1);
+    let memory = Arc::new(Semaphore::new(memory_budget_mb));
+    let mut join_set = JoinSet::new();
+    for spec in specs {
+        let spec = spec.clone();
+        let concurrency = Arc::clone(&concurrency);
+        let memory = Arc::clone(&memory);
+        // a file larger than the budget is generated on its own
+        let memory_mb = spec
+            .estimated_memory_mb(&options)
+            .clamp(1, memory_budget_mb) as u32;
+        join_set.spawn(async move {
+            // always acquire memory first to avoid deadlocks
+            let _memory = memory.acquire_many_owned(memory_mb).await.unwrap();
+            let _permit = concurrency.acquire_owned().await.unwrap();
+            spec.create(&options).await;
+        });
     }
-    row_group_encoder.close().await
+    join_set.join_all().await;
+    println!("Done generating files, {}", format_peak_rss());
 }
 
-struct RowGroupEncoder {
-    // tasks for encoding column chunks. Return (column_index, column_chunk)
-    join_set: JoinSet<(usize, ArrowColumnChunk)>,
-    /// channel to send arrays to column writers
-    writer_txs: Vec<tokio::sync::mpsc::Sender<ArrayRef>>,
+/// Converts an arrow 56 [`RecordBatch`] (as created by [`FileType::create_batch`])
+/// to arrow 57 using the IPC format
+fn arrow_56_to_57(batch: RecordBatch) -> arrow_57::array::RecordBatch {
+    let mut buffer = vec![];
+    {
+        let mut writer =
+            arrow::ipc::writer::StreamWriter::try_new(&mut buffer, &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+    }
+    let mut reader =
+        arrow_57::ipc::reader::StreamReader::try_new(std::io::Cursor::new(buffer), None).unwrap();
+    reader.next().unwrap().unwrap()
 }
 
-impl RowGroupEncoder {
-    fn new(schema: SchemaRef, column_writers: Vec<ArrowColumnWriter>) -> Self {
-        // setup the channel and tasks
-        let mut join_set = JoinSet::new();
-        let mut writer_txs = Vec::with_capacity(column_writers.len());
-        for (column_index, mut writer) in column_writers.into_iter().enumerate() {
-            let buffer_size = 2;
-            let (writer_tx, mut writer_rx) = tokio::sync::mpsc::channel(buffer_size);
-            let field = Arc::clone(&schema.fields()[column_index]);
-            let writer_task = async move {
-                // receive arrays and write them
-                while let Some(array) = writer_rx.recv().await {
-                    let leaves = compute_leaves(&field, &array).unwrap();
-                    for leaf in leaves {
-                        writer.write(&leaf).unwrap();
+/// Generates a module that writes the file described by a [`ParquetFileSpec`]
+/// with a specific version of the parquet crate.
+///
+/// Arguments:
+/// * `$module`: name of the module to generate (also the name of the [`crate::footer`] module)
+/// * `$parquet` / `$arrow`: the parquet crate and the arrow crate it uses
+/// * `$sorting_column`: the `SortingColumn` type (moved in parquet 57)
+/// * `$convert_batch`: converts the generated arrow 56 batches to `$arrow`
+macro_rules! writer_module {
+    ($module:ident, $parquet:ident, $arrow:ident, $sorting_column:ty, $convert_batch:path) => {
+        pub(crate) mod $module {
+            use super::{
+                BATCH_ROWS, DISTINCT_BATCH_FACTOR, DISTINCT_NULL_DENSITY, GenerationOptions,
+                NULL_DENSITY, ParquetFileSpec,
+            };
+            use crate::encryption;
+            use crate::footer;
+            use crate::memory::format_peak_rss;
+            use crate::writer_options::{
+                Codec, ColumnEncoding, ColumnOverride, StatisticsLevel, WriterVersion,
+            };
+            use std::fs::File;
+            use std::sync::Arc;
+            use tokio::task::JoinSet;
+            use $arrow::array::{ArrayRef, RecordBatch};
+            use $arrow::datatypes::SchemaRef;
+            use $parquet::arrow::ArrowWriter;
+            use $parquet::arrow::arrow_writer::{
+                ArrowColumnChunk, ArrowColumnWriter, compute_leaves,
+            };
+            use $parquet::basic::{Compression, Encoding, GzipLevel, ZstdLevel};
+            use $parquet::file::properties::{
+                EnabledStatistics, WriterProperties, WriterPropertiesBuilder,
+                WriterVersion as ParquetWriterVersion,
+            };
+            use $parquet::schema::types::ColumnPath;
+
+            type SortingColumn = $sorting_column;
+
+            /// Writes the file described by `spec` to `file`
+            pub async fn write(
+                spec: &ParquetFileSpec,
+                options: &GenerationOptions,
+                file: &mut File,
+            ) {
+                let num_rows = spec.rows_per_row_group.min(BATCH_ROWS);
+                // use the same batch repeatedly otherwise the data generation will dominate
+                // the time. For distinct row groups, generate a larger batch once and use
+                // a different window of each column for each row group
+                let (cached_rows, null_density) = if spec.distinct_row_groups {
+                    (num_rows * DISTINCT_BATCH_FACTOR, DISTINCT_NULL_DENSITY)
+                } else {
+                    (num_rows, NULL_DENSITY)
+                };
+                let cached_batch = $convert_batch(spec.file_type.create_batch(
+                    spec.seed,
+                    cached_rows,
+                    spec.columns,
+                    null_density,
+                ));
+                let props = writer_properties(spec);
+                let writer =
+                    ArrowWriter::try_new(&mut *file, cached_batch.schema(), Some(props)).unwrap();
+
+                let (mut file_writer, row_group_factory) = writer.into_serialized_writer().unwrap();
+
+                let column_group_size = options.column_group_size.unwrap_or(spec.columns).max(1);
+                let mut rows_written = 0;
+                for rg in 0..spec.row_groups {
+                    // the row group factory creates the writers of all the columns at
+                    // once (with the column encryptors of encrypted files), and each
+                    // group takes the writers of its columns
+                    let mut column_writers = row_group_factory
+                        .create_column_writers(rg)
+                        .unwrap()
+                        .into_iter();
+                    let mut rg_writer = file_writer.next_row_group().unwrap();
+                    let batch = if spec.distinct_row_groups {
+                        let offsets = spec.row_group_offsets(rg, cached_rows - num_rows);
+                        let columns = cached_batch
+                            .columns()
+                            .iter()
+                            .zip(offsets)
+                            .map(|(column, offset)| column.slice(offset, num_rows))
+                            .collect();
+                        RecordBatch::try_new(cached_batch.schema(), columns).unwrap()
+                    } else {
+                        cached_batch.clone()
+                    };
+                    // encode the columns in groups, appending each group to the row group
+                    // before starting the next, so only one group is encoded at a time
+                    let mut first_column = 0;
+                    while first_column < spec.columns {
+                        let last_column = (first_column + column_group_size).min(spec.columns);
+                        let group_columns: Vec<_> = (first_column..last_column).collect();
+                        let group_batch = batch.project(&group_columns).unwrap();
+                        let group_writers =
+                            column_writers.by_ref().take(group_columns.len()).collect();
+                        let row_group_encoder =
+                            RowGroupEncoder::new(group_batch.schema(), group_writers);
+
+                        let encoded_columns = encode_row_group(
+                            &group_batch,
+                            spec.row_group_num_rows(rg),
+                            row_group_encoder,
+                        )
+                        .await;
+                        for col_chunk in encoded_columns.into_iter() {
+                            col_chunk.append_to_row_group(&mut rg_writer).unwrap();
+                        }
+                        first_column += group_columns.len();
                     }
+                    let rg_metadata = rg_writer.close().unwrap();
+                    let mb_size = rg_metadata.total_byte_size() as f64 / (1024.0 * 1024.0);
+                    println!(
+                        "[{}] Completed row group {}/{} of {} columns, {} rows {mb_size:.02} MB, \
+                         {}",
+                        spec.label(),
+                        rg + 1,
+                        spec.row_groups,
+                        rg_metadata.num_columns(),
+                        rg_metadata.num_rows(),
+                        format_peak_rss(),
+                    );
+                    rows_written += rg_metadata.num_rows();
                 }
-                // when no more arrays, close the writer and return the column chunk
-                let arrow_column_chunk = writer.close().unwrap();
-                (column_index, arrow_column_chunk)
-            };
-            join_set.spawn(writer_task);
-            writer_txs.push(writer_tx);
+                file_writer.close().unwrap();
+                if !spec.encoding_stats {
+                    footer::$module::rewrite_footer(
+                        file,
+                        footer::$module::clear_page_encoding_stats,
+                    );
+                }
+                println!(
+                    "[{}] Wrote {rows_written} rows to {:?}",
+                    spec.label(),
+                    spec.path
+                );
+            }
+
+            /// Returns the [`WriterProperties`] to use to write the file
+            fn writer_properties(spec: &ParquetFileSpec) -> WriterProperties {
+                let writer_version = match spec.writer_version {
+                    WriterVersion::Parquet1_0 => ParquetWriterVersion::PARQUET_1_0,
+                    WriterVersion::Parquet2_0 => ParquetWriterVersion::PARQUET_2_0,
+                };
+                let mut builder = WriterProperties::builder()
+                    .set_max_row_group_size(spec.rows_per_row_group)
+                    .set_writer_version(writer_version);
+                let schema = spec.file_type.schema(spec.columns);
+                for (column_index, field) in schema.fields().iter().enumerate() {
+                    let column = ColumnPath::from(field.name().as_str());
+                    builder = builder.set_column_compression(
+                        column.clone(),
+                        compression(spec.codec(column_index)),
+                    );
+                    builder = apply_encoding(builder, column.clone(), spec.encoding(column_index));
+                    for column_override in &spec.column_overrides {
+                        if column_override.applies_to(column_index) {
+                            builder = apply_override(builder, column.clone(), column_override);
+                        }
+                    }
+                }
+                if let Some(sorting_columns) = &spec.sorting_columns {
+                    let sorting_columns = (0..spec.columns)
+                        .filter(|column_index| sorting_columns.contains(*column_index))
+                        .map(|column_index| SortingColumn {
+                            column_idx: column_index as i32,
+                            descending: false,
+                            nulls_first: true,
+                        })
+                        .collect();
+                    builder = builder.set_sorting_columns(Some(sorting_columns));
+                }
+                let column_names = spec.column_names();
+                builder =
+                    encryption::with_encryption!($parquet, builder, spec.encryption, &column_names);
+                builder.build()
+            }
+
+            /// The parquet compression for `codec` (using the default level)
+            pub(crate) fn compression(codec: Codec) -> Compression {
+                match codec {
+                    Codec::Uncompressed => Compression::UNCOMPRESSED,
+                    Codec::Snappy => Compression::SNAPPY,
+                    Codec::Zstd => Compression::ZSTD(ZstdLevel::default()),
+                    Codec::Lz4Raw => Compression::LZ4_RAW,
+                    Codec::Gzip => Compression::GZIP(GzipLevel::default()),
+                }
+            }
+
+            /// Configure the writer properties to use `encoding` for `column`
+            fn apply_encoding(
+                builder: WriterPropertiesBuilder,
+                column: ColumnPath,
+                encoding: ColumnEncoding,
+            ) -> WriterPropertiesBuilder {
+                let encoding = match encoding {
+                    ColumnEncoding::Dictionary => {
+                        return builder.set_column_dictionary_enabled(column, true);
+                    }
+                    ColumnEncoding::Plain => Encoding::PLAIN,
+                    ColumnEncoding::DeltaBinaryPacked => Encoding::DELTA_BINARY_PACKED,
+                    ColumnEncoding::ByteStreamSplit => Encoding::BYTE_STREAM_SPLIT,
+                };
+                builder
+                    .set_column_dictionary_enabled(column.clone(), false)
+                    .set_column_encoding(column, encoding)
+            }
+
+            /// Configure the writer properties with `column_override` for `column`
+            fn apply_override(
+                mut builder: WriterPropertiesBuilder,
+                column: ColumnPath,
+                column_override: &ColumnOverride,
+            ) -> WriterPropertiesBuilder {
+                if let Some(statistics) = column_override.statistics() {
+                    let statistics = match statistics {
+                        StatisticsLevel::None => EnabledStatistics::None,
+                        StatisticsLevel::Chunk => EnabledStatistics::Chunk,
+                        StatisticsLevel::Page => EnabledStatistics::Page,
+                    };
+                    builder = builder.set_column_statistics_enabled(column.clone(), statistics);
+                }
+                if let Some(dictionary) = column_override.dictionary() {
+                    builder = builder.set_column_dictionary_enabled(column.clone(), dictionary);
+                }
+                if let Some(bloom_filter) = column_override.bloom_filter() {
+                    builder = builder.set_column_bloom_filter_enabled(column, bloom_filter);
+                }
+                builder
+            }
+
+            async fn encode_row_group(
+                batch: &RecordBatch,
+                rows_per_row_group: usize,
+                mut row_group_encoder: RowGroupEncoder,
+            ) -> Vec<ArrowColumnChunk> {
+                let mut rows_written = 0;
+                while rows_written < rows_per_row_group {
+                    let rows_left = rows_per_row_group - rows_written;
+                    let batch = if rows_left < batch.num_rows() {
+                        batch.slice(0, rows_left)
+                    } else {
+                        batch.clone()
+                    };
+                    row_group_encoder.encode_batch(&batch).await;
+                    rows_written += batch.num_rows();
+                }
+                row_group_encoder.close().await
+            }
+
+            struct RowGroupEncoder {
+                // tasks for encoding column chunks. Return (column_index, column_chunk)
+                join_set: JoinSet<(usize, ArrowColumnChunk)>,
+                /// channel to send arrays to column writers
+                writer_txs: Vec<tokio::sync::mpsc::Sender<ArrayRef>>,
+            }
+
+            impl RowGroupEncoder {
+                fn new(schema: SchemaRef, column_writers: Vec<ArrowColumnWriter>) -> Self {
+                    // setup the channel and tasks
+                    let mut join_set = JoinSet::new();
+                    let mut writer_txs = Vec::with_capacity(column_writers.len());
+                    for (column_index, mut writer) in column_writers.into_iter().enumerate() {
+                        let buffer_size = 2;
+                        let (writer_tx, mut writer_rx) = tokio::sync::mpsc::channel(buffer_size);
+                        let field = Arc::clone(&schema.fields()[column_index]);
+                        let writer_task = async move {
+                            // receive arrays and write them
+                            while let Some(array) = writer_rx.recv().await {
+                                let leaves = compute_leaves(&field, &array).unwrap();
+                                for leaf in leaves {
+                                    writer.write(&leaf).unwrap();
+                                }
+                            }
+                            // when no more arrays, close the writer and return the column chunk
+                            let arrow_column_chunk = writer.close().unwrap();
+                            (column_index, arrow_column_chunk)
+                        };
+                        join_set.spawn(writer_task);
+                        writer_txs.push(writer_tx);
+                    }
+
+                    Self {
+                        join_set,
+                        writer_txs,
+                    }
+                }
+
+                /// Encode the next batch, sending arrays to column writers on separate tasks to
+                /// encode in parallel.
+                async fn encode_batch(&mut self, batch: &RecordBatch) {
+                    for (array, tx) in batch.columns().iter().zip(self.writer_txs.iter()) {
+                        let array = array.clone();
+                        // send will fail if the receiver has been dropped
+                        let tx = tx.clone();
+                        if let Err(e) = tx.send(array).await {
+                            eprintln!("Failed to send array to writer: {}", e);
+                            return;
+                        }
+                    }
+                }
+
+                // Completes the row group and returns the column chunks
+                async fn close(self) -> Vec<ArrowColumnChunk> {
+                    // close all the senders to signal no more arrays
+                    drop(self.writer_txs);
+                    // collect all the column chunks from the tasks
+                    // (note need to reorder this correctly)
+                    let mut completed_columns = self.join_set.join_all().await;
+                    // sort by column index
+                    completed_columns.sort_by_key(|res| res.0);
+                    // extract the column chunks
+                    completed_columns.into_iter().map(|res| res.1).collect()
+                }
+            }
         }
+    };
+}
 
-        Self {
-            join_set,
-            writer_txs,
+writer_module!(
+    v56,
+    parquet_56,
+    arrow,
+    parquet_56::format::SortingColumn,
+    std::convert::identity
+);
+writer_module!(
+    v57,
+    parquet_57,
+    arrow_57,
+    parquet_57::file::metadata::SortingColumn,
+    crate::parquet_file::arrow_56_to_57
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sized_spec(row_group_sizes: RowGroupSizes, trailing_rows: Option<usize>) -> ParquetFileSpec {
+        let mut builder = ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Float)
+            .with_columns(1)
+            .with_row_groups(5)
+            .with_rows_per_row_group(1000)
+            .with_row_group_sizes(row_group_sizes);
+        if let Some(trailing_rows) = trailing_rows {
+            builder = builder.with_trailing_rows(trailing_rows);
         }
+        builder.build()
     }
 
-    /// Encode the next batch, sending arrays to column writers on separate tasks to encode
-    /// in parallel.
-    async fn encode_batch(&mut self, batch: &RecordBatch) {
-        for (array, tx) in batch.columns().iter().zip(self.writer_txs.iter()) {
-            let array = array.clone();
-            // send will fail if the receiver has been dropped
-            let tx = tx.clone();
-            if let Err(e) = tx.send(array).await {
-                eprintln!("Failed to send array to writer: {}", e);
-                return;
-            }
+    fn num_rows(spec: &ParquetFileSpec) -> Vec<usize> {
+        (0..spec.row_groups())
+            .map(|row_group| spec.row_group_num_rows(row_group))
+            .collect()
+    }
+
+    #[test]
+    fn fixed_row_group_sizes() {
+        let spec = sized_spec(RowGroupSizes::Fixed, None);
+        assert_eq!(num_rows(&spec), vec![1000; 5]);
+    }
+
+    #[test]
+    fn random_range_row_group_sizes() {
+        let sizes = RowGroupSizes::RandomRange {
+            min: 100,
+            max: 5000,
+        };
+        let rows = num_rows(&sized_spec(sizes, None));
+        assert!(
+            rows.iter().all(|rows| (100..=5000).contains(rows)),
+            "{rows:?}"
+        );
+        // seeded, so the same for every spec with the same seed
+        assert_eq!(rows, num_rows(&sized_spec(sizes, None)));
+        assert!(rows.windows(2).any(|pair| pair[0] != pair[1]), "{rows:?}");
+    }
+
+    #[test]
+    fn geometric_row_group_sizes() {
+        let spec = sized_spec(RowGroupSizes::Geometric { ratio: 0.5 }, None);
+        // never fewer than 1 row
+        assert_eq!(num_rows(&spec), vec![1000, 500, 250, 125, 63]);
+        let spec = ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Float)
+            .with_columns(1)
+            .with_row_groups(3)
+            .with_rows_per_row_group(1)
+            .with_row_group_sizes(RowGroupSizes::Geometric { ratio: 0.1 })
+            .build();
+        assert_eq!(num_rows(&spec), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn trailing_rows() {
+        let spec = sized_spec(RowGroupSizes::Fixed, Some(10));
+        assert_eq!(num_rows(&spec), vec![1000, 1000, 1000, 1000, 10]);
+        let spec = sized_spec(RowGroupSizes::Geometric { ratio: 0.5 }, Some(7));
+        assert_eq!(num_rows(&spec), vec![1000, 500, 250, 125, 7]);
+    }
+
+    /// Row groups growing by a ratio above 1 would soon not fit in memory
+    #[test]
+    #[should_panic(expected = "invalid geometric row group size ratio 2")]
+    fn rejects_growing_row_groups() {
+        sized_spec(RowGroupSizes::Geometric { ratio: 2.0 }, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "trailing_rows must be between 1 and 999 rows")]
+    fn rejects_full_trailing_row_group() {
+        sized_spec(RowGroupSizes::Fixed, Some(1000));
+    }
+
+    #[test]
+    fn column_override_codec_and_encoding() {
+        let column_override: ColumnOverride = toml::from_str(
+            "columns = { list = [1] }\ncompression = \"ZSTD\"\nencoding = \"DELTA_BINARY_PACKED\"",
+        )
+        .unwrap();
+        let spec = ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Int64)
+            .with_columns(3)
+            .with_row_groups(1)
+            .with_rows_per_row_group(1000)
+            .with_compression(vec![Codec::Snappy])
+            .with_encodings(vec![ColumnEncoding::Plain])
+            .with_column_override(column_override)
+            .build();
+        let codecs: Vec<_> = (0..3).map(|column| spec.codec(column)).collect();
+        assert_eq!(codecs, vec![Codec::Snappy, Codec::Zstd, Codec::Snappy]);
+        let encodings: Vec<_> = (0..3).map(|column| spec.encoding(column)).collect();
+        assert_eq!(
+            encodings,
+            vec![
+                ColumnEncoding::Plain,
+                ColumnEncoding::DeltaBinaryPacked,
+                ColumnEncoding::Plain
+            ]
+        );
+    }
+
+    /// An empty cached batch would never fill the row groups
+    #[test]
+    #[should_panic(expected = "rows_per_row_group must not be 0")]
+    fn rejects_empty_row_groups() {
+        ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Float)
+            .with_columns(1)
+            .with_row_groups(5)
+            .with_rows_per_row_group(0)
+            .with_row_group_sizes(RowGroupSizes::Geometric { ratio: 0.5 })
+            .build();
+    }
+
+    fn wide_spec(footer_only: bool) -> ParquetFileSpec {
+        ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Float)
+            .with_columns(1000)
+            .with_row_groups(100)
+            .with_rows_per_row_group(100_000)
+            .with_footer_only(footer_only)
+            .build()
+    }
+
+    #[test]
+    fn estimated_memory_mb() {
+        let options = GenerationOptions::default();
+        // 1000 columns of 400KB encoded twice, plus 16KB per column writer
+        assert_eq!(wide_spec(false).estimated_memory_mb(&options), 778);
+        // only the columns of one group are in flight, but all the writers exist
+        let grouped = GenerationOptions {
+            column_group_size: Some(100),
+            ..options
+        };
+        assert_eq!(wide_spec(false).estimated_memory_mb(&grouped), 91);
+        // roughly 1KB per column chunk of the decoded metadata
+        assert_eq!(wide_spec(true).estimated_memory_mb(&options), 97);
+    }
+
+    /// All the files are created even when the memory budget only allows one
+    /// file at a time, and a file larger than the budget does not deadlock
+    #[tokio::test]
+    async fn create_all_within_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let specs: Vec<_> = [(10, 100), (20, 100), (10, 50_000)]
+            .into_iter()
+            .map(|(columns, rows_per_row_group)| {
+                ParquetFileSpecBuilder::new()
+                    .with_path(
+                        dir.path()
+                            .join(format!("{columns}_{rows_per_row_group}.parquet")),
+                    )
+                    .with_file_type(FileType::Int64)
+                    .with_columns(columns)
+                    .with_row_groups(2)
+                    .with_rows_per_row_group(rows_per_row_group)
+                    .build()
+            })
+            .collect();
+        let options = GenerationOptions {
+            concurrency: 0,
+            memory_budget_mb: 1,
+            column_group_size: None,
+        };
+        // larger than the budget, so generated on its own
+        assert!(specs[2].estimated_memory_mb(&options) > 1);
+
+        create_all(&specs, options).await;
+        for spec in &specs {
+            assert_eq!(Manifest::check(spec), Ok(()), "{spec}");
+        }
+    }
+
+    /// Generating the same spec twice produces the same bytes and manifest, even
+    /// with a seed large enough to wrap around when deriving the column seeds
+    #[tokio::test]
+    async fn generation_is_reproducible() {
+        let dir = tempfile::tempdir().unwrap();
+        for writer_crate in [WriterCrate::Parquet56, WriterCrate::Parquet57] {
+            let spec = ParquetFileSpecBuilder::new()
+                .with_path(dir.path().join(format!("{writer_crate:?}.parquet")))
+                .with_file_type(FileType::Float)
+                .with_columns(10)
+                .with_row_groups(3)
+                .with_rows_per_row_group(100)
+                .with_seed(u64::MAX)
+                .with_distinct_row_groups(true)
+                .with_writer_crate(writer_crate)
+                .build();
+            let manifest_path = dir
+                .path()
+                .join(format!("{writer_crate:?}.parquet.manifest.json"));
+
+            spec.create(&GenerationOptions::default()).await;
+            let bytes = fs::read(spec.path()).unwrap();
+            let manifest = fs::read_to_string(&manifest_path).unwrap();
+
+            fs::remove_file(spec.path()).unwrap();
+            fs::remove_file(&manifest_path).unwrap();
+            spec.create(&GenerationOptions::default()).await;
+            assert!(bytes == fs::read(spec.path()).unwrap(), "{spec} differs");
+            assert_eq!(manifest, fs::read_to_string(&manifest_path).unwrap());
         }
     }
 
-    // Completes the row group and returns the column chunks
-    async fn close(self) -> Vec<ArrowColumnChunk> {
-        // close all the senders to signal no more arrays
-        drop(self.writer_txs);
-        // collect all the column chunks from the tasks
-        // (note need to reorder this correctly)
-        let mut completed_columns = self.join_set.join_all().await;
-        // sort by column index
-        completed_columns.sort_by_key(|res| res.0);
-        // extract the column chunks
-        completed_columns.into_iter().map(|res| res.1).collect()
+    /// With distinct row groups the min, max and null count of every column
+    /// differ between row groups
+    #[tokio::test]
+    async fn distinct_row_group_statistics() {
+        use parquet_56::file::metadata::ParquetMetaDataReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let spec = ParquetFileSpecBuilder::new()
+            .with_path(dir.path().join("distinct.parquet"))
+            .with_file_type(FileType::Int64)
+            .with_columns(5)
+            .with_row_groups(10)
+            .with_rows_per_row_group(1000)
+            .with_distinct_row_groups(true)
+            .build();
+        spec.create(&GenerationOptions::default()).await;
+        let file = fs::File::open(spec.path()).unwrap();
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&file)
+            .unwrap();
+
+        fn differ<T: PartialEq>(values: &[T]) -> bool {
+            values.windows(2).any(|pair| pair[0] != pair[1])
+        }
+        for column in 0..spec.columns() {
+            let statistics: Vec<_> = metadata
+                .row_groups()
+                .iter()
+                .map(|row_group| row_group.column(column).statistics().unwrap())
+                .collect();
+            let min: Vec<_> = statistics.iter().map(|s| s.min_bytes_opt()).collect();
+            let max: Vec<_> = statistics.iter().map(|s| s.max_bytes_opt()).collect();
+            let null_count: Vec<_> = statistics.iter().map(|s| s.null_count_opt()).collect();
+            assert!(differ(&min), "column {column} min");
+            assert!(differ(&max), "column {column} max");
+            assert!(
+                differ(&null_count),
+                "column {column} null count {null_count:?}"
+            );
+        }
     }
 }
@@ -0,0 +1,260 @@
+//! Hardware performance counters
+//!
+//! With the `perf-counters` cargo feature on Linux, CPU counters (instructions
+//! retired, cycles, branch misses, L1 data cache and last level cache misses)
+//! of the current thread are read with `perf_event_open` around every timed
+//! region (see [`crate::timed_region`]), and accumulated separately for the
+//! metadata and page index phases. Instruction counts are far less noisy than
+//! wall clock time on shared machines.
+//!
+//! If the counters can not be opened (for example when
+//! `/proc/sys/kernel/perf_event_paranoid` does not allow it) or fail later, a
+//! warning is printed and nothing more is counted. When the kernel multiplexes
+//! the counters with other events, the counts are scaled by the time the group
+//! was enabled over the time it was running, and if the group did not run at
+//! all in a timed region, the counts of that benchmark are not reported.
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+/// True if the counters are read (the `perf-counters` feature is enabled on Linux)
+pub const ENABLED: bool = cfg!(all(feature = "perf-counters", target_os = "linux"));
+
+/// Hardware counter values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfCounts {
+    pub instructions: u64,
+    pub cycles: u64,
+    pub branch_misses: u64,
+    /// L1 data cache read misses
+    pub l1d_misses: u64,
+    /// last level cache misses
+    pub llc_misses: u64,
+}
+
+impl PerfCounts {
+    const ZERO: Self = Self {
+        instructions: 0,
+        cycles: 0,
+        branch_misses: 0,
+        l1d_misses: 0,
+        llc_misses: 0,
+    };
+
+    /// Average counts of `runs` runs
+    pub fn per_run(&self, runs: usize) -> Self {
+        let runs = runs.max(1) as u64;
+        Self {
+            instructions: self.instructions / runs,
+            cycles: self.cycles / runs,
+            branch_misses: self.branch_misses / runs,
+            l1d_misses: self.l1d_misses / runs,
+            llc_misses: self.llc_misses / runs,
+        }
+    }
+}
+
+impl AddAssign for PerfCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.instructions += other.instructions;
+        self.cycles += other.cycles;
+        self.branch_misses += other.branch_misses;
+        self.l1d_misses += other.l1d_misses;
+        self.llc_misses += other.llc_misses;
+    }
+}
+
+/// The phase of a `run_once` closure a timed region measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Metadata,
+    Index,
+}
+
+/// Counter values of the metadata and page index phases
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseCounts {
+    pub metadata: PerfCounts,
+    pub index: PerfCounts,
+}
+
+impl PhaseCounts {
+    const ZERO: Self = Self {
+        metadata: PerfCounts::ZERO,
+        index: PerfCounts::ZERO,
+    };
+
+    /// Average counts of `runs` runs
+    pub fn per_run(&self, runs: usize) -> Self {
+        Self {
+            metadata: self.metadata.per_run(runs),
+            index: self.index.per_run(runs),
+        }
+    }
+}
+
+thread_local! {
+    static TOTALS: Cell<PhaseCounts> = const { Cell::new(PhaseCounts::ZERO) };
+    /// true if the counters did not run in a timed region since the last [`take`]
+    static MISSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Starts counting on the current thread
+pub fn start() {
+    #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+    linux::start();
+}
+
+/// Stops counting on the current thread, adding the counts since [`start`] to
+/// the totals of `phase`
+pub fn stop(phase: Phase) {
+    #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+    match linux::stop() {
+        Some(Some(counts)) => TOTALS.with(|cell| {
+            let mut totals = cell.get();
+            match phase {
+                Phase::Metadata => totals.metadata += counts,
+                Phase::Index => totals.index += counts,
+            }
+            cell.set(totals);
+        }),
+        Some(None) => MISSED.set(true),
+        None => {}
+    }
+    #[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+    let _ = phase;
+}
+
+/// Returns the totals of the current thread and resets them to zero, or `None`
+/// if the counters are not read or did not run in one of the timed regions
+pub fn take() -> Option<PhaseCounts> {
+    let totals = TOTALS.with(|cell| cell.replace(PhaseCounts::ZERO));
+    let missed = MISSED.replace(false);
+    (available() && !missed).then_some(totals)
+}
+
+/// Returns true if the counters of the current thread could be opened
+fn available() -> bool {
+    #[cfg(all(feature = "perf-counters", target_os = "linux"))]
+    {
+        linux::available()
+    }
+    #[cfg(not(all(feature = "perf-counters", target_os = "linux")))]
+    {
+        false
+    }
+}
+
+#[cfg(all(feature = "perf-counters", target_os = "linux"))]
+mod linux {
+    use super::PerfCounts;
+    use perf_event::events::{Cache, CacheOp, CacheResult, Hardware, WhichCache};
+    use perf_event::{Builder, Counter, Group};
+    use std::cell::RefCell;
+
+    /// The counters of one thread, read together as a group
+    struct Counters {
+        group: Group,
+        instructions: Counter,
+        cycles: Counter,
+        branch_misses: Counter,
+        l1d_misses: Counter,
+        llc_misses: Counter,
+    }
+
+    impl Counters {
+        fn open() -> std::io::Result<Self> {
+            let mut group = Group::new()?;
+            let l1d_read_misses = Cache {
+                which: WhichCache::L1D,
+                operation: CacheOp::READ,
+                result: CacheResult::MISS,
+            };
+            Ok(Self {
+                instructions: Builder::new()
+                    .group(&mut group)
+                    .kind(Hardware::INSTRUCTIONS)
+                    .build()?,
+                cycles: Builder::new()
+                    .group(&mut group)
+                    .kind(Hardware::CPU_CYCLES)
+                    .build()?,
+                branch_misses: Builder::new()
+                    .group(&mut group)
+                    .kind(Hardware::BRANCH_MISSES)
+                    .build()?,
+                l1d_misses: Builder::new()
+                    .group(&mut group)
+                    .kind(l1d_read_misses)
+                    .build()?,
+                llc_misses: Builder::new()
+                    .group(&mut group)
+                    .kind(Hardware::CACHE_MISSES)
+                    .build()?,
+                group,
+            })
+        }
+    }
+
+    thread_local! {
+        static COUNTERS: RefCell<Option<Counters>> = RefCell::new(
+            Counters::open()
+                .inspect_err(|e| println!("Hardware performance counters unavailable: {e}"))
+                .ok(),
+        );
+    }
+
+    /// Stops counting on the current thread after the counters failed
+    fn disable(counters: &mut Option<Counters>, e: std::io::Error) {
+        println!("Hardware performance counters failed, no longer counting: {e}");
+        *counters = None;
+    }
+
+    pub fn available() -> bool {
+        COUNTERS.with_borrow(|counters| counters.is_some())
+    }
+
+    pub fn start() {
+        COUNTERS.with_borrow_mut(|counters| {
+            if let Some(group) = counters.as_mut().map(|counters| &mut counters.group)
+                && let Err(e) = group.reset().and_then(|()| group.enable())
+            {
+                disable(counters, e);
+            }
+        });
+    }
+
+    /// Returns the counts since [`start`], `Some(None)` if the counters did not
+    /// run at all, or `None` if they are not read
+    pub fn stop() -> Option<Option<PerfCounts>> {
+        COUNTERS.with_borrow_mut(|counters| {
+            let group = &mut counters.as_mut()?.group;
+            let counts = match group.disable().and_then(|()| group.read()) {
+                Ok(counts) => counts,
+                Err(e) => {
+                    disable(counters, e);
+                    return None;
+                }
+            };
+            let counters = counters.as_ref()?;
+            let (enabled, running) = (counts.time_enabled(), counts.time_running());
+            if running == 0 {
+                return Some(None);
+            }
+            // scale up the counts if the kernel multiplexed the counters
+            let scale = |count: u64| {
+                if running < enabled {
+                    (count as u128 * enabled as u128 / running as u128) as u64
+                } else {
+                    count
+                }
+            };
+            Some(Some(PerfCounts {
+                instructions: scale(counts[&counters.instructions]),
+                cycles: scale(counts[&counters.cycles]),
+                branch_misses: scale(counts[&counters.branch_misses]),
+                l1d_misses: scale(counts[&counters.l1d_misses]),
+                llc_misses: scale(counts[&counters.llc_misses]),
+            }))
+        })
+    }
+}
@@ -0,0 +1,318 @@
+//! Metadata loading from a simulated object store
+//!
+//! When files live in object storage, loading the footer is dominated by
+//! request round trips rather than parsing. [`SimulatedStore`] serves the
+//! generated files from the local filesystem, adding a fixed latency to every
+//! request and limiting the bandwidth, and counts the requests and bytes read.
+//! [`ObjectStoreBenchmark`] loads the metadata and page indexes through
+//! `ParquetObjectReader` with the released parquet 57 async reader.
+use crate::encryption::decryption_properties;
+use crate::parquet_file::ParquetFileSpec;
+use async_trait::async_trait;
+use comfy_table::Table;
+use futures::stream::BoxStream;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path as ObjectPath;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult,
+};
+use parquet_57::arrow::async_reader::ParquetObjectReader;
+use parquet_57::encryption::decrypt::FileDecryptionProperties;
+use parquet_57::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+use serde::Deserialize;
+use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Settings of the simulated object store, from the benchmark matrix file
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectStoreOptions {
+    /// added to every request, in milliseconds
+    latency_ms: u64,
+    /// maximum transfer rate, in MB/s (unlimited if not set)
+    bandwidth_mb_per_sec: Option<f64>,
+    /// bytes the reader fetches from the end of the file in its first request
+    /// (the parquet default of 8 bytes if not set)
+    prefetch_hint: Option<usize>,
+    /// number of times the metadata is loaded from each file (at least 1)
+    #[serde(default = "default_object_store_runs")]
+    runs: NonZeroUsize,
+}
+
+fn default_object_store_runs() -> NonZeroUsize {
+    NonZeroUsize::new(3).unwrap()
+}
+
+impl Display for ObjectStoreOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "latency {}ms", self.latency_ms)?;
+        match self.bandwidth_mb_per_sec {
+            Some(bandwidth) => write!(f, ", bandwidth {bandwidth} MB/s")?,
+            None => write!(f, ", unlimited bandwidth")?,
+        }
+        if let Some(prefetch_hint) = self.prefetch_hint {
+            write!(f, ", prefetch {prefetch_hint} bytes")?;
+        }
+        Ok(())
+    }
+}
+
+/// An [`ObjectStore`] that delays every read by the configured latency and
+/// bandwidth, and counts the requests and bytes read
+#[derive(Debug)]
+pub struct SimulatedStore {
+    inner: LocalFileSystem,
+    latency: Duration,
+    /// bytes per second
+    bandwidth: Option<f64>,
+    requests: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl Display for SimulatedStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SimulatedStore({})", self.inner)
+    }
+}
+
+impl SimulatedStore {
+    pub fn new(options: &ObjectStoreOptions) -> Self {
+        Self {
+            // no prefix: the files are addressed by their absolute path (see
+            // `ObjectStoreBenchmark::new`)
+            inner: LocalFileSystem::new(),
+            latency: Duration::from_millis(options.latency_ms),
+            bandwidth: options.bandwidth_mb_per_sec.map(|mb| mb * 1_000_000.0),
+            requests: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the (requests, bytes) read since the last call
+    fn take_counts(&self) -> (u64, u64) {
+        (
+            self.requests.swap(0, Ordering::Relaxed),
+            self.bytes.swap(0, Ordering::Relaxed),
+        )
+    }
+}
+
+#[async_trait]
+impl ObjectStore for SimulatedStore {
+    async fn put_opts(
+        &self,
+        location: &ObjectPath,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &ObjectPath,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    /// All reads (including `get_range` and `get_ranges`) go through here
+    async fn get_opts(
+        &self,
+        location: &ObjectPath,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let head = options.head;
+        let result = self.inner.get_opts(location, options).await?;
+        let bytes = if head {
+            0
+        } else {
+            result.range.end - result.range.start
+        };
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+        let transfer = self
+            .bandwidth
+            .map(|bandwidth| Duration::from_secs_f64(bytes as f64 / bandwidth))
+            .unwrap_or_default();
+        tokio::time::sleep(self.latency + transfer).await;
+        Ok(result)
+    }
+
+    async fn delete(&self, location: &ObjectPath) -> object_store::Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&ObjectPath>,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&ObjectPath>,
+    ) -> object_store::Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &ObjectPath, to: &ObjectPath) -> object_store::Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(
+        &self,
+        from: &ObjectPath,
+        to: &ObjectPath,
+    ) -> object_store::Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+/// Loads the metadata and both page indexes of a file from a
+/// [`SimulatedStore`]
+pub struct ObjectStoreBenchmark {
+    description: String,
+    path: ObjectPath,
+    file_len: u64,
+    store: Arc<SimulatedStore>,
+    options: ObjectStoreOptions,
+    /// keys of encrypted files
+    decryption: Option<Arc<FileDecryptionProperties>>,
+}
+
+impl ObjectStoreBenchmark {
+    pub fn new(spec: &ParquetFileSpec, options: ObjectStoreOptions) -> Self {
+        let absolute_path = std::fs::canonicalize(spec.path()).unwrap();
+        Self {
+            description: spec.to_string(),
+            path: ObjectPath::from_absolute_path(&absolute_path).unwrap(),
+            file_len: std::fs::metadata(&absolute_path).unwrap().len(),
+            store: Arc::new(SimulatedStore::new(&options)),
+            options,
+            decryption: decryption_properties!(parquet_57, spec.encryption(), &spec.column_names()),
+        }
+    }
+
+    /// Loads the metadata `runs` times, returning the averages
+    pub async fn run(&self, parse_duration: Option<Duration>) -> ObjectStoreResult {
+        println!(
+            "Loading metadata from simulated object store ({})...",
+            self.options
+        );
+        let mut requests = 0;
+        let mut bytes = 0;
+        let mut latency = Duration::from_secs(0);
+        for _ in 0..self.options.runs.get() {
+            let mut reader = ParquetObjectReader::new(self.store.clone(), self.path.clone())
+                .with_file_size(self.file_len);
+            let start = Instant::now();
+            let _metadata = ParquetMetaDataReader::new()
+                .with_column_index_policy(PageIndexPolicy::Optional)
+                .with_offset_index_policy(PageIndexPolicy::Optional)
+                .with_prefetch_hint(self.options.prefetch_hint)
+                .with_decryption_properties(self.decryption.clone())
+                .load_and_finish(&mut reader, self.file_len)
+                .await
+                .unwrap();
+            latency += start.elapsed();
+            let (run_requests, run_bytes) = self.store.take_counts();
+            requests += run_requests;
+            bytes += run_bytes;
+        }
+        let runs = self.options.runs.get() as u64;
+        ObjectStoreResult {
+            description: self.description.clone(),
+            requests: requests / runs,
+            bytes: bytes / runs,
+            latency: latency / runs as u32,
+            parse_duration,
+        }
+    }
+}
+
+/// Average requests, bytes read and time to load the metadata of one file
+#[derive(Debug)]
+pub struct ObjectStoreResult {
+    description: String,
+    requests: u64,
+    bytes: u64,
+    /// end to end time to load the metadata and page indexes
+    latency: Duration,
+    /// time to parse the metadata and page indexes from memory
+    parse_duration: Option<Duration>,
+}
+
+impl ObjectStoreResult {
+    pub fn set_headers(table: &mut Table) {
+        table.set_header(vec![
+            "Description",
+            "Requests",
+            "Bytes Fetched",
+            "End to End Latency",
+            "Parse Time (Arrow 57 ParquetMetaDataReader)",
+            "Parse Share",
+        ]);
+    }
+
+    pub fn add_to_table(&self, table: &mut Table) {
+        let (parse_duration, share) = match self.parse_duration {
+            Some(parse_duration) => (
+                format!("{parse_duration:?}"),
+                format!(
+                    "{:.1}%",
+                    parse_duration.as_secs_f64() / self.latency.as_secs_f64() * 100.0
+                ),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        table.add_row(vec![
+            self.description.clone(),
+            self.requests.to_string(),
+            self.bytes.to_string(),
+            format!("{:?}", self.latency),
+            parse_duration,
+            share,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn options(toml: &str) -> Result<ObjectStoreOptions, toml::de::Error> {
+        toml::from_str(toml)
+    }
+
+    #[test]
+    fn runs_must_not_be_zero() {
+        assert!(options("latency_ms = 0\nruns = 0").is_err());
+        assert_eq!(options("latency_ms = 0").unwrap().runs.get(), 3);
+        assert_eq!(options("latency_ms = 0\nruns = 1").unwrap().runs.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn counts_requests_and_bytes() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&[0; 1000]).unwrap();
+        let path = ObjectPath::from_absolute_path(file.path()).unwrap();
+        let store = SimulatedStore::new(&options("latency_ms = 0").unwrap());
+        assert_eq!(store.take_counts(), (0, 0));
+
+        store.get_range(&path, 0..10).await.unwrap();
+        store.get_range(&path, 900..1000).await.unwrap();
+        assert_eq!(store.take_counts(), (2, 110));
+        // the counts are reset by take_counts
+        assert_eq!(store.take_counts(), (0, 0));
+
+        store.get(&path).await.unwrap();
+        assert_eq!(store.take_counts(), (1, 1000));
+    }
+}
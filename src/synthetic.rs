@@ -0,0 +1,461 @@
+//! Footer-only synthetic parquet files
+//!
+//! Metadata parsing does not need real data pages, and encoding data makes
+//! files with 1M columns or 10,000 row groups impractical to create. Instead,
+//! this builds the [`ParquetMetaData`] (schema, row groups, column chunks,
+//! statistics and page indexes) directly, and writes a valid footer and page
+//! indexes after a placeholder (zero filled, sparse) data region.
+//!
+//! The offsets and sizes in the metadata are consistent with the layout the
+//! writer would produce, but the data pages themselves can not be read, so
+//! these files are only useful for benchmarking footer and page index parsing.
+//! Bloom filters and dictionary overrides are not written.
+use crate::file_type::FileType;
+use crate::parquet_file::{ParquetFileSpec, v56};
+use crate::writer_options::{ColumnEncoding, StatisticsLevel, WriterVersion};
+use parquet_56::arrow::ArrowSchemaConverter;
+use parquet_56::basic::{Encoding, PageType};
+use parquet_56::data_type::ByteArray;
+use parquet_56::file::metadata::{
+    ColumnChunkMetaData, FileMetaData, ParquetMetaData, ParquetMetaDataBuilder,
+    ParquetMetaDataReader, ParquetMetaDataWriter, RowGroupMetaData,
+};
+use parquet_56::file::page_encoding_stats::PageEncodingStats;
+use parquet_56::file::page_index::index::{Index, NativeIndex, PageIndex};
+use parquet_56::file::page_index::offset_index::OffsetIndexMetaData;
+use parquet_56::file::properties::DEFAULT_CREATED_BY;
+use parquet_56::file::statistics::Statistics;
+use parquet_56::format::{BoundaryOrder, PageLocation, SortingColumn};
+use parquet_56::schema::types::ColumnDescPtr;
+use rand::distr::Alphanumeric;
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::sync::Arc;
+
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+/// Size of the footer tail (metadata length + `PAR1` magic)
+const FOOTER_SIZE: usize = 8;
+/// Maximum number of rows in a data page (the parquet-rs writer default)
+const MAX_ROWS_PER_PAGE: usize = 20_000;
+
+/// Writes a footer-only file described by `spec` to `file`
+pub fn write_footer_only(spec: &ParquetFileSpec, file: &mut File) {
+    let (metadata, data_end) = build_metadata(spec);
+
+    // The metadata writer computes page index offsets relative to the start of
+    // its output, so first write the page indexes and footer to a buffer, and
+    // then rewrite the footer with the page index offsets shifted past the data
+    let mut buffer = vec![];
+    ParquetMetaDataWriter::new(&mut buffer, &metadata)
+        .finish()
+        .unwrap();
+    drop(metadata);
+    let tail: [u8; FOOTER_SIZE] = buffer[buffer.len() - FOOTER_SIZE..].try_into().unwrap();
+    let metadata_length = ParquetMetaDataReader::decode_footer_tail(&tail)
+        .unwrap()
+        .metadata_length();
+    let index_end = buffer.len() - FOOTER_SIZE - metadata_length;
+    let metadata =
+        ParquetMetaDataReader::decode_metadata(&buffer[index_end..buffer.len() - FOOTER_SIZE])
+            .unwrap();
+    let metadata = shift_page_index_offsets(metadata, data_end as i64);
+
+    file.write_all(PARQUET_MAGIC).unwrap();
+    // placeholder data region (sparse on most file systems)
+    file.set_len(data_end).unwrap();
+    file.seek(SeekFrom::Start(data_end)).unwrap();
+    file.write_all(&buffer[..index_end]).unwrap();
+    ParquetMetaDataWriter::new(&mut *file, &metadata)
+        .finish()
+        .unwrap();
+    println!(
+        "[{}] Wrote footer only file: {data_end} bytes of placeholder data, {index_end} bytes \
+         of page indexes, {metadata_length} bytes of metadata",
+        spec.label()
+    );
+}
+
+/// Builds the metadata (with page indexes) for `spec`, returning the metadata
+/// and the end offset of the data region
+fn build_metadata(spec: &ParquetFileSpec) -> (ParquetMetaData, u64) {
+    let arrow_schema = spec.file_type().schema(spec.columns());
+    let schema_descr = Arc::new(ArrowSchemaConverter::new().convert(&arrow_schema).unwrap());
+    let mut rng = StdRng::seed_from_u64(spec.seed());
+
+    let sorting_columns = spec.sorting_columns().map(|sorting_columns| {
+        (0..spec.columns())
+            .filter(|column_index| sorting_columns.contains(*column_index))
+            .map(|column_index| SortingColumn {
+                column_idx: column_index as i32,
+                descending: false,
+                nulls_first: true,
+            })
+            .collect()
+    });
+
+    // data starts after the leading magic
+    let mut offset = PARQUET_MAGIC.len() as i64;
+    let mut row_groups = Vec::with_capacity(spec.row_groups());
+    let mut column_index = Vec::with_capacity(spec.row_groups());
+    let mut offset_index = Vec::with_capacity(spec.row_groups());
+    let mut num_rows = 0;
+    for row_group_index in 0..spec.row_groups() {
+        let rows = spec.row_group_num_rows(row_group_index);
+        let pages = rows.div_ceil(MAX_ROWS_PER_PAGE).max(1);
+        let rows_per_page = rows.div_ceil(pages);
+        let page_size = rows_per_page.max(1) * spec.file_type().estimated_value_size();
+        let mut columns = Vec::with_capacity(spec.columns());
+        let mut row_group_column_index = Vec::with_capacity(spec.columns());
+        let mut row_group_offset_index = Vec::with_capacity(spec.columns());
+        let mut total_byte_size = 0;
+        for column in 0..spec.columns() {
+            let chunk = ColumnChunkBuilder {
+                spec,
+                column_descr: schema_descr.column(column),
+                column,
+                rows,
+                pages,
+                rows_per_page,
+                page_size,
+            };
+            let (metadata, index, locations) = chunk.build(offset, &mut rng);
+            offset += metadata.compressed_size();
+            total_byte_size += metadata.uncompressed_size();
+            columns.push(metadata);
+            row_group_column_index.push(index);
+            row_group_offset_index.push(locations);
+        }
+        let row_group = RowGroupMetaData::builder(Arc::clone(&schema_descr))
+            .set_num_rows(rows as i64)
+            .set_total_byte_size(total_byte_size)
+            .set_column_metadata(columns)
+            .set_sorting_columns(sorting_columns.clone())
+            .build()
+            .unwrap();
+        row_groups.push(row_group);
+        num_rows += rows;
+        column_index.push(row_group_column_index);
+        offset_index.push(row_group_offset_index);
+    }
+
+    let version = match spec.writer_version() {
+        WriterVersion::Parquet1_0 => 1,
+        WriterVersion::Parquet2_0 => 2,
+    };
+    let file_metadata = FileMetaData::new(
+        version,
+        num_rows as i64,
+        Some(DEFAULT_CREATED_BY.to_string()),
+        None,
+        schema_descr,
+        None,
+    );
+    let metadata = ParquetMetaDataBuilder::new(file_metadata)
+        .set_row_groups(row_groups)
+        .set_column_index(Some(column_index))
+        .set_offset_index(Some(offset_index))
+        .build();
+    (metadata, offset as u64)
+}
+
+/// Builds the metadata for a single column chunk
+struct ColumnChunkBuilder<'a> {
+    spec: &'a ParquetFileSpec,
+    column_descr: ColumnDescPtr,
+    /// index of the column in the schema
+    column: usize,
+    rows: usize,
+    pages: usize,
+    rows_per_page: usize,
+    /// size of each (placeholder) page in bytes
+    page_size: usize,
+}
+
+impl ColumnChunkBuilder<'_> {
+    /// Returns the column chunk metadata, column index and offset index for a
+    /// column chunk starting at `offset`
+    fn build(
+        &self,
+        offset: i64,
+        rng: &mut StdRng,
+    ) -> (ColumnChunkMetaData, Index, OffsetIndexMetaData) {
+        let encoding = self.spec.encoding(self.column);
+        let data_page_type = match self.spec.writer_version() {
+            WriterVersion::Parquet1_0 => PageType::DATA_PAGE,
+            WriterVersion::Parquet2_0 => PageType::DATA_PAGE_V2,
+        };
+        let mut builder = ColumnChunkMetaData::builder(Arc::clone(&self.column_descr))
+            .set_compression(v56::compression(self.spec.codec(self.column)))
+            .set_num_values(self.rows as i64);
+
+        // dictionary encoded chunks start with a dictionary page
+        let (data_page_offset, data_encoding, mut encodings, mut encoding_stats) = match encoding {
+            ColumnEncoding::Dictionary => {
+                builder = builder.set_dictionary_page_offset(Some(offset));
+                let dictionary_page_stats = PageEncodingStats {
+                    page_type: PageType::DICTIONARY_PAGE,
+                    encoding: Encoding::PLAIN,
+                    count: 1,
+                };
+                (
+                    offset + self.page_size as i64,
+                    Encoding::RLE_DICTIONARY,
+                    vec![Encoding::PLAIN, Encoding::RLE, Encoding::RLE_DICTIONARY],
+                    vec![dictionary_page_stats],
+                )
+            }
+            ColumnEncoding::Plain => (offset, Encoding::PLAIN, vec![Encoding::PLAIN], vec![]),
+            ColumnEncoding::DeltaBinaryPacked => (
+                offset,
+                Encoding::DELTA_BINARY_PACKED,
+                vec![Encoding::DELTA_BINARY_PACKED],
+                vec![],
+            ),
+            ColumnEncoding::ByteStreamSplit => (
+                offset,
+                Encoding::BYTE_STREAM_SPLIT,
+                vec![Encoding::BYTE_STREAM_SPLIT],
+                vec![],
+            ),
+        };
+        if !encodings.contains(&Encoding::RLE) {
+            // definition levels
+            encodings.push(Encoding::RLE);
+        }
+        encoding_stats.push(PageEncodingStats {
+            page_type: data_page_type,
+            encoding: data_encoding,
+            count: self.pages as i32,
+        });
+
+        let page_locations: Vec<_> = (0..self.pages)
+            .map(|page| PageLocation {
+                offset: data_page_offset + (page * self.page_size) as i64,
+                compressed_page_size: self.page_size as i32,
+                first_row_index: (page * self.rows_per_page) as i64,
+            })
+            .collect();
+        let chunk_size = data_page_offset - offset + (self.pages * self.page_size) as i64;
+        builder = builder
+            .set_encodings(encodings)
+            .set_data_page_offset(data_page_offset)
+            .set_total_compressed_size(chunk_size)
+            .set_total_uncompressed_size(chunk_size);
+        if self.spec.encoding_stats() {
+            builder = builder.set_page_encoding_stats(encoding_stats);
+        }
+
+        let statistics_level = self.spec.statistics_level(self.column);
+        let (statistics, index) = random_statistics(self.spec.file_type(), self.pages, rng);
+        let index = match statistics_level {
+            StatisticsLevel::None => Index::NONE,
+            StatisticsLevel::Chunk => {
+                builder = builder.set_statistics(statistics);
+                Index::NONE
+            }
+            StatisticsLevel::Page => {
+                builder = builder.set_statistics(statistics);
+                index
+            }
+        };
+
+        let offset_index = OffsetIndexMetaData {
+            page_locations,
+            unencoded_byte_array_data_bytes: None,
+        };
+        (builder.build().unwrap(), index, offset_index)
+    }
+}
+
+/// Macro that creates an unordered column index from page indexes (a macro, as
+/// the value type bound of `NativeIndex` is not public)
+macro_rules! native_index {
+    ($indexes:expr) => {
+        NativeIndex {
+            indexes: $indexes,
+            boundary_order: BoundaryOrder::UNORDERED,
+        }
+    };
+}
+
+/// Returns random column chunk statistics and a column index with `pages` pages
+fn random_statistics(file_type: FileType, pages: usize, rng: &mut StdRng) -> (Statistics, Index) {
+    match file_type {
+        FileType::Float => {
+            let (min, max, indexes) = page_indexes(pages, || rng.random::<f32>());
+            let statistics = Statistics::float(Some(min), Some(max), None, Some(0), false);
+            (statistics, Index::FLOAT(native_index!(indexes)))
+        }
+        FileType::Int64 => {
+            let (min, max, indexes) = page_indexes(pages, || rng.random::<i64>());
+            let statistics = Statistics::int64(Some(min), Some(max), None, Some(0), false);
+            (statistics, Index::INT64(native_index!(indexes)))
+        }
+        FileType::String => {
+            let (min, max, indexes) = page_indexes(pages, || {
+                let len = rng.random_range(0..20);
+                let value: String = (&mut *rng)
+                    .sample_iter(&Alphanumeric)
+                    .take(len)
+                    .map(char::from)
+                    .collect();
+                ByteArray::from(value.as_str())
+            });
+            let statistics = Statistics::byte_array(Some(min), Some(max), None, Some(0), false);
+            (statistics, Index::BYTE_ARRAY(native_index!(indexes)))
+        }
+    }
+}
+
+/// Creates a random min / max per page using `random`, returning the overall
+/// min, max and the page indexes
+fn page_indexes<T: Clone + PartialOrd>(
+    pages: usize,
+    mut random: impl FnMut() -> T,
+) -> (T, T, Vec<PageIndex<T>>) {
+    let page_indexes: Vec<PageIndex<T>> = (0..pages)
+        .map(|_| {
+            let (a, b) = (random(), random());
+            let (min, max) = if a <= b { (a, b) } else { (b, a) };
+            PageIndex {
+                min: Some(min),
+                max: Some(max),
+                null_count: Some(0),
+                repetition_level_histogram: None,
+                definition_level_histogram: None,
+            }
+        })
+        .collect();
+    let mut min = page_indexes[0].min.clone().unwrap();
+    let mut max = page_indexes[0].max.clone().unwrap();
+    for page_index in &page_indexes[1..] {
+        let (page_min, page_max) = (
+            page_index.min.as_ref().unwrap(),
+            page_index.max.as_ref().unwrap(),
+        );
+        if *page_min < min {
+            min = page_min.clone();
+        }
+        if *page_max > max {
+            max = page_max.clone();
+        }
+    }
+    (min, max, page_indexes)
+}
+
+/// Adds `shift` to the column index and offset index offsets of every column chunk
+fn shift_page_index_offsets(metadata: ParquetMetaData, shift: i64) -> ParquetMetaData {
+    let mut builder = metadata.into_builder();
+    let row_groups = builder
+        .take_row_groups()
+        .into_iter()
+        .map(|row_group| {
+            let columns = row_group
+                .columns()
+                .iter()
+                .map(|column| {
+                    let column_index_offset = column.column_index_offset().map(|o| o + shift);
+                    let offset_index_offset = column.offset_index_offset().map(|o| o + shift);
+                    column
+                        .clone()
+                        .into_builder()
+                        .set_column_index_offset(column_index_offset)
+                        .set_offset_index_offset(offset_index_offset)
+                        .build()
+                        .unwrap()
+                })
+                .collect();
+            row_group
+                .into_builder()
+                .set_column_metadata(columns)
+                .build()
+                .unwrap()
+        })
+        .collect();
+    builder.set_row_groups(row_groups).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parquet_file::ParquetFileSpecBuilder;
+    use crate::writer_options::ColumnOverride;
+    use bytes::Bytes;
+    use parquet_56::file::metadata::PageIndexPolicy;
+    use std::path::PathBuf;
+
+    /// A footer only file whose first column has no statistics (and so no
+    /// column index)
+    fn spec() -> ParquetFileSpec {
+        let no_statistics: ColumnOverride =
+            toml::from_str("columns = { first = 1 }\nstatistics = \"none\"").unwrap();
+        ParquetFileSpecBuilder::new()
+            .with_path(PathBuf::from("test.parquet"))
+            .with_file_type(FileType::Int64)
+            .with_columns(3)
+            .with_row_groups(2)
+            .with_rows_per_row_group(50_000)
+            .with_footer_only(true)
+            .with_column_override(no_statistics)
+            .build()
+    }
+
+    /// The (column index, offset index) offsets of every column chunk
+    fn page_index_offsets(metadata: &ParquetMetaData) -> Vec<(Option<i64>, Option<i64>)> {
+        metadata
+            .row_groups()
+            .iter()
+            .flat_map(|row_group| row_group.columns())
+            .map(|column| (column.column_index_offset(), column.offset_index_offset()))
+            .collect()
+    }
+
+    #[test]
+    fn shifts_page_index_offsets() {
+        let (metadata, _) = build_metadata(&spec());
+        let mut buffer = vec![];
+        ParquetMetaDataWriter::new(&mut buffer, &metadata)
+            .finish()
+            .unwrap();
+        let metadata = ParquetMetaDataReader::new()
+            .parse_and_finish(&Bytes::from(buffer))
+            .unwrap();
+        let offsets = page_index_offsets(&metadata);
+        assert_eq!(offsets[0].0, None);
+        assert!(offsets[1].0.is_some() && offsets[0].1.is_some());
+
+        let shifted = page_index_offsets(&shift_page_index_offsets(metadata, 1000));
+        let expected: Vec<_> = offsets
+            .iter()
+            .map(|(column_index, offset_index)| {
+                (
+                    column_index.map(|o| o + 1000),
+                    offset_index.map(|o| o + 1000),
+                )
+            })
+            .collect();
+        assert_eq!(shifted, expected);
+    }
+
+    #[test]
+    fn footer_only_page_indexes_are_readable() {
+        let spec = spec();
+        let mut file = tempfile::tempfile().unwrap();
+        write_footer_only(&spec, &mut file);
+        let metadata = ParquetMetaDataReader::new()
+            .with_page_index_policy(PageIndexPolicy::Required)
+            .parse_and_finish(&file)
+            .unwrap();
+        let (expected, data_end) = build_metadata(&spec);
+
+        // the page indexes are written after the placeholder data region
+        for (column_index, offset_index) in page_index_offsets(&metadata) {
+            assert!(column_index.is_none_or(|offset| offset as u64 >= data_end));
+            assert!(offset_index.is_some_and(|offset| offset as u64 >= data_end));
+        }
+        assert_eq!(metadata.column_index(), expected.column_index());
+        assert_eq!(metadata.offset_index(), expected.offset_index());
+    }
+}
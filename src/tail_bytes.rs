@@ -0,0 +1,105 @@
+//! The end of a parquet file held in memory
+//!
+//! `ParquetMetaDataReader` and `SerializedFileReader` read through a
+//! `ChunkReader`. [`TailBytes`] serves the page indexes, metadata and footer
+//! from memory, so those readers are timed on the same preloaded bytes as the
+//! push decoders rather than on file I/O.
+use bytes::{Buf, Bytes};
+
+/// The last bytes of a file, addressed by their offset in the file
+#[derive(Debug, Clone)]
+pub struct TailBytes {
+    /// length of the whole file
+    file_len: u64,
+    /// offset of the first byte of `bytes` in the file
+    offset: u64,
+    bytes: Bytes,
+}
+
+impl TailBytes {
+    /// `bytes` are the last bytes of a file of `file_len` bytes
+    pub fn new(file_len: u64, bytes: Bytes) -> Self {
+        let offset = file_len - bytes.len() as u64;
+        Self {
+            file_len,
+            offset,
+            bytes,
+        }
+    }
+
+    fn slice(&self, start: u64, length: usize) -> Result<Bytes, String> {
+        let end = start + length as u64;
+        if start < self.offset || end > self.file_len {
+            return Err(format!(
+                "bytes {start}..{end} were not preloaded (preloaded {}..{})",
+                self.offset, self.file_len
+            ));
+        }
+        let start = (start - self.offset) as usize;
+        Ok(self.bytes.slice(start..start + length))
+    }
+}
+
+/// Implements the `ChunkReader` trait of the `$parquet` crate for [`TailBytes`]
+macro_rules! impl_chunk_reader {
+    ($parquet:ident) => {
+        impl $parquet::file::reader::Length for TailBytes {
+            fn len(&self) -> u64 {
+                self.file_len
+            }
+        }
+
+        impl $parquet::file::reader::ChunkReader for TailBytes {
+            type T = bytes::buf::Reader<Bytes>;
+
+            fn get_read(&self, start: u64) -> $parquet::errors::Result<Self::T> {
+                let length = self.file_len.saturating_sub(start) as usize;
+                self.slice(start, length)
+                    .map(|bytes| bytes.reader())
+                    .map_err($parquet::errors::ParquetError::General)
+            }
+
+            fn get_bytes(&self, start: u64, length: usize) -> $parquet::errors::Result<Bytes> {
+                self.slice(start, length)
+                    .map_err($parquet::errors::ParquetError::General)
+            }
+        }
+    };
+}
+
+impl_chunk_reader!(parquet_56);
+impl_chunk_reader!(parquet_57);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet_57::file::reader::ChunkReader;
+    use std::io::Read;
+
+    /// The last 10 bytes (90..100) of a 100 byte file
+    fn tail() -> TailBytes {
+        TailBytes::new(100, Bytes::from_iter(90..100u8))
+    }
+
+    #[test]
+    fn slices_by_file_offset() {
+        let tail = tail();
+        assert_eq!(tail.get_bytes(90, 3).unwrap().as_ref(), &[90, 91, 92]);
+        assert_eq!(tail.get_bytes(98, 2).unwrap().as_ref(), &[98, 99]);
+        assert_eq!(tail.get_bytes(100, 0).unwrap().len(), 0);
+
+        let mut rest = vec![];
+        tail.get_read(95).unwrap().read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, vec![95, 96, 97, 98, 99]);
+    }
+
+    #[test]
+    fn rejects_ranges_not_preloaded() {
+        let tail = tail();
+        // before the preloaded bytes
+        assert!(tail.get_bytes(89, 2).is_err());
+        assert!(tail.get_read(0).is_err());
+        // past the end of the file
+        assert!(tail.get_bytes(99, 2).is_err());
+    }
+}
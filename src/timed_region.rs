@@ -0,0 +1,36 @@
+//! The timed regions of the benchmarks
+//!
+//! Every timed region of a `run_once` closure is measured by a [`TimedRegion`],
+//! so anything else measured about the decoders (the allocations, see
+//! [`crate::allocations`], and the hardware counters, see
+//! [`crate::perf_counters`]) covers exactly the code that is timed.
+use crate::allocations;
+use crate::perf_counters::{self, Phase};
+use std::time::{Duration, Instant};
+
+/// A region of code being timed on the current thread
+pub struct TimedRegion {
+    phase: Phase,
+    start: Instant,
+}
+
+impl TimedRegion {
+    /// Starts a region measuring `phase`
+    pub fn start(phase: Phase) -> Self {
+        perf_counters::start();
+        allocations::set_counting(true);
+        Self {
+            phase,
+            start: Instant::now(),
+        }
+    }
+
+    /// Ends the region, returning the time since it started
+    pub fn elapsed(self) -> Duration {
+        let elapsed = self.start.elapsed();
+        allocations::set_counting(false);
+        // reading the counters allocates, so stop counting allocations first
+        perf_counters::stop(self.phase);
+        elapsed
+    }
+}
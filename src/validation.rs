@@ -0,0 +1,193 @@
+//! Validation of generated files against their [`ParquetFileSpec`]
+//!
+//! Each file is reopened after it is written and its metadata is compared to
+//! the spec, to catch generator bugs before they skew benchmark results.
+use crate::encryption::decryption_properties;
+use crate::parquet_file::{ParquetFileSpec, v56};
+use crate::writer_options::{ColumnEncoding, StatisticsLevel};
+use parquet_56::arrow::parquet_to_arrow_schema;
+use parquet_56::basic::Encoding;
+use parquet_56::file::metadata::{PageIndexPolicy, ParquetMetaDataReader};
+use parquet_56::file::page_index::index::Index;
+use std::fmt::Display;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of mismatches shown when displaying a [`ValidationError`]
+const MAX_DISPLAYED_MISMATCHES: usize = 20;
+
+/// The differences between a file and the spec it was generated from
+#[derive(Debug)]
+pub struct ValidationError {
+    path: PathBuf,
+    mismatches: Vec<String>,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?} does not match its spec:", self.path)?;
+        // wide files can have a mismatch for every column chunk
+        for mismatch in self.mismatches.iter().take(MAX_DISPLAYED_MISMATCHES) {
+            writeln!(f, "  {mismatch}")?;
+        }
+        if self.mismatches.len() > MAX_DISPLAYED_MISMATCHES {
+            let remaining = self.mismatches.len() - MAX_DISPLAYED_MISMATCHES;
+            writeln!(f, "  ... and {remaining} more")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks the file at `path` (written from `spec`) has the column count, row
+/// group count, rows per row group, schema, codecs, encodings, statistics, page
+/// indexes and bloom filters described by `spec`
+pub fn validate(spec: &ParquetFileSpec, path: &Path) -> Result<(), ValidationError> {
+    let file = File::open(path).unwrap();
+    let decryption = decryption_properties!(parquet_56, spec.encryption(), &spec.column_names());
+    // parquet 56 can read the files written by either writer
+    let metadata = ParquetMetaDataReader::new()
+        .with_page_index_policy(PageIndexPolicy::Required)
+        .with_decryption_properties(decryption.as_deref())
+        .parse_and_finish(&file)
+        .map_err(|e| ValidationError {
+            path: path.to_path_buf(),
+            mismatches: vec![format!("can not read metadata: {e}")],
+        })?;
+
+    let mut mismatches = vec![];
+    let file_metadata = metadata.file_metadata();
+    let schema_descr = file_metadata.schema_descr();
+    if schema_descr.num_columns() != spec.columns() {
+        mismatches.push(format!(
+            "{} columns, expected {}",
+            schema_descr.num_columns(),
+            spec.columns()
+        ));
+    }
+    if metadata.num_row_groups() != spec.row_groups() {
+        mismatches.push(format!(
+            "{} row groups, expected {}",
+            metadata.num_row_groups(),
+            spec.row_groups()
+        ));
+    }
+
+    // compare the types without the embedded arrow schema, as footer only files
+    // do not have one
+    match parquet_to_arrow_schema(schema_descr, None) {
+        Ok(schema) => {
+            let expected = spec.file_type().schema(spec.columns());
+            for (field, expected_field) in schema.fields().iter().zip(expected.fields()) {
+                if field.name() != expected_field.name()
+                    || field.data_type() != expected_field.data_type()
+                {
+                    mismatches.push(format!(
+                        "column {} has type {}, expected {} {}",
+                        field.name(),
+                        field.data_type(),
+                        expected_field.name(),
+                        expected_field.data_type()
+                    ));
+                }
+            }
+        }
+        Err(e) => mismatches.push(format!("can not convert schema to arrow: {e}")),
+    }
+
+    let column_index = metadata.column_index();
+    let offset_index = metadata.offset_index();
+    if offset_index.is_none() {
+        mismatches.push("no offset index".to_string());
+    }
+    for (rg, row_group) in metadata.row_groups().iter().enumerate() {
+        let rows = row_group.num_rows() as usize;
+        let expected_rows = spec.row_group_num_rows(rg);
+        if rows != expected_rows {
+            mismatches.push(format!(
+                "row group {rg} has {rows} rows, expected {expected_rows}"
+            ));
+        }
+        for (column, column_chunk) in row_group.columns().iter().enumerate() {
+            let codec = spec.codec(column);
+            if column_chunk.compression() != v56::compression(codec) {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: compression {}, expected {codec}",
+                    column_chunk.compression()
+                ));
+            }
+            let encodings = column_chunk.encodings();
+            let has_dictionary = column_chunk.dictionary_page_offset().is_some()
+                && encodings.iter().any(|encoding| {
+                    matches!(
+                        encoding,
+                        Encoding::PLAIN_DICTIONARY | Encoding::RLE_DICTIONARY
+                    )
+                });
+            if has_dictionary != spec.dictionary_enabled(column) {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: dictionary encoded: {has_dictionary}, \
+                     expected {}",
+                    spec.dictionary_enabled(column)
+                ));
+            }
+            // without a dictionary, the data pages use the configured encoding
+            let expected_encoding = match spec.encoding(column) {
+                ColumnEncoding::Dictionary => None,
+                ColumnEncoding::Plain => Some(Encoding::PLAIN),
+                ColumnEncoding::DeltaBinaryPacked => Some(Encoding::DELTA_BINARY_PACKED),
+                ColumnEncoding::ByteStreamSplit => Some(Encoding::BYTE_STREAM_SPLIT),
+            };
+            if let Some(expected_encoding) = expected_encoding
+                && !has_dictionary
+                && !encodings.contains(&expected_encoding)
+            {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: encodings {encodings:?}, expected \
+                     {expected_encoding}"
+                ));
+            }
+            let has_bloom_filter = column_chunk.bloom_filter_offset().is_some();
+            if has_bloom_filter != spec.bloom_filter(column) {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: bloom filter present: {has_bloom_filter}, \
+                     expected {}",
+                    spec.bloom_filter(column)
+                ));
+            }
+            let statistics_level = spec.statistics_level(column);
+            let has_statistics = column_chunk.statistics().is_some();
+            if has_statistics != (statistics_level != StatisticsLevel::None) {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: statistics present: {has_statistics}, \
+                     expected statistics level {statistics_level}"
+                ));
+            }
+            let has_column_index = column_index
+                .and_then(|column_index| column_index.get(rg)?.get(column))
+                .is_some_and(|index| !matches!(index, Index::NONE));
+            if has_column_index != (statistics_level == StatisticsLevel::Page) {
+                mismatches.push(format!(
+                    "row group {rg} column {column}: column index present: {has_column_index}, \
+                     expected statistics level {statistics_level}"
+                ));
+            }
+            let has_offset_index = offset_index
+                .and_then(|offset_index| offset_index.get(rg)?.get(column))
+                .is_some_and(|offset_index| !offset_index.page_locations().is_empty());
+            if !has_offset_index {
+                mismatches.push(format!("row group {rg} column {column}: no offset index"));
+            }
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError {
+            path: path.to_path_buf(),
+            mismatches,
+        })
+    }
+}
@@ -0,0 +1,328 @@
+//! Options that control how the parquet writer encodes each column chunk
+//!
+//! These are independent of the parquet crate version, and are converted to
+//! the `WriterProperties` of each version when writing files
+use serde::Deserialize;
+use std::fmt::Display;
+
+/// Compression codec used for a column chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Codec {
+    Uncompressed,
+    Snappy,
+    Zstd,
+    Lz4Raw,
+    Gzip,
+}
+
+impl Display for Codec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Codec::Uncompressed => write!(f, "UNCOMPRESSED"),
+            Codec::Snappy => write!(f, "SNAPPY"),
+            Codec::Zstd => write!(f, "ZSTD"),
+            Codec::Lz4Raw => write!(f, "LZ4_RAW"),
+            Codec::Gzip => write!(f, "GZIP"),
+        }
+    }
+}
+
+/// Encoding used for the data pages of a column chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ColumnEncoding {
+    /// PLAIN encoding, dictionary disabled
+    Plain,
+    /// Dictionary encoding (falls back to PLAIN if the dictionary gets too large)
+    Dictionary,
+    /// DELTA_BINARY_PACKED, dictionary disabled (integer columns only)
+    DeltaBinaryPacked,
+    /// BYTE_STREAM_SPLIT, dictionary disabled (fixed width columns only)
+    ByteStreamSplit,
+}
+
+impl Display for ColumnEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnEncoding::Plain => write!(f, "PLAIN"),
+            ColumnEncoding::Dictionary => write!(f, "DICTIONARY"),
+            ColumnEncoding::DeltaBinaryPacked => write!(f, "DELTA_BINARY_PACKED"),
+            ColumnEncoding::ByteStreamSplit => write!(f, "BYTE_STREAM_SPLIT"),
+        }
+    }
+}
+
+/// Parquet format version written by the writer.
+///
+/// This also selects the data page version: `PARQUET_1_0` writes data page v1,
+/// `PARQUET_2_0` writes data page v2 (and uses different fallback encodings)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum WriterVersion {
+    #[default]
+    #[serde(rename = "PARQUET_1_0")]
+    Parquet1_0,
+    #[serde(rename = "PARQUET_2_0")]
+    Parquet2_0,
+}
+
+impl Display for WriterVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriterVersion::Parquet1_0 => write!(f, "PARQUET_1_0"),
+            WriterVersion::Parquet2_0 => write!(f, "PARQUET_2_0"),
+        }
+    }
+}
+
+/// Level of statistics written for a column chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatisticsLevel {
+    /// No statistics (and no column index)
+    None,
+    /// Statistics in the column chunk metadata only
+    Chunk,
+    /// Statistics in the column chunk metadata and the column index
+    Page,
+}
+
+impl Display for StatisticsLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatisticsLevel::None => write!(f, "none"),
+            StatisticsLevel::Chunk => write!(f, "chunk"),
+            StatisticsLevel::Page => write!(f, "page"),
+        }
+    }
+}
+
+/// Selects a subset of the columns in a file, by column index
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnSelection {
+    /// All columns
+    All,
+    /// The first `k` columns
+    First(usize),
+    /// Every `n`th column, starting with the first
+    EveryNth(usize),
+    /// An explicit list of column indexes
+    List(Vec<usize>),
+}
+
+impl Display for ColumnSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnSelection::All => write!(f, "all cols"),
+            ColumnSelection::First(k) => write!(f, "first {k} cols"),
+            ColumnSelection::EveryNth(1) => write!(f, "all cols"),
+            ColumnSelection::EveryNth(n) => write!(f, "every {} col", ordinal(*n)),
+            ColumnSelection::List(columns) => write!(f, "cols {columns:?}"),
+        }
+    }
+}
+
+/// Formats `n` as an English ordinal ("2nd", "3rd", "11th", "21st")
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+impl ColumnSelection {
+    /// Returns true if the column at `column_index` is selected
+    pub fn contains(&self, column_index: usize) -> bool {
+        match self {
+            ColumnSelection::All => true,
+            ColumnSelection::First(k) => column_index < *k,
+            ColumnSelection::EveryNth(n) => *n > 0 && column_index.is_multiple_of(*n),
+            ColumnSelection::List(columns) => columns.contains(&column_index),
+        }
+    }
+}
+
+/// Writer property overrides for a subset of columns.
+///
+/// Settings that are `None` are left unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnOverride {
+    columns: ColumnSelection,
+    compression: Option<Codec>,
+    encoding: Option<ColumnEncoding>,
+    statistics: Option<StatisticsLevel>,
+    dictionary: Option<bool>,
+    bloom_filter: Option<bool>,
+}
+
+impl Display for ColumnOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut settings = vec![];
+        if let Some(compression) = self.compression {
+            settings.push(format!("codec={compression}"));
+        }
+        if let Some(encoding) = self.encoding {
+            settings.push(format!("encoding={encoding}"));
+        }
+        if let Some(statistics) = self.statistics {
+            settings.push(format!("stats={statistics}"));
+        }
+        if let Some(dictionary) = self.dictionary {
+            settings.push(format!("dict={dictionary}"));
+        }
+        if let Some(bloom_filter) = self.bloom_filter {
+            settings.push(format!("bloom={bloom_filter}"));
+        }
+        write!(f, "{} on {}", settings.join(" "), self.columns)
+    }
+}
+
+impl ColumnOverride {
+    /// Returns true if this override applies to the column at `column_index`
+    pub fn applies_to(&self, column_index: usize) -> bool {
+        self.columns.contains(column_index)
+    }
+    pub fn compression(&self) -> Option<Codec> {
+        self.compression
+    }
+    pub fn encoding(&self) -> Option<ColumnEncoding> {
+        self.encoding
+    }
+    pub fn statistics(&self) -> Option<StatisticsLevel> {
+        self.statistics
+    }
+    pub fn dictionary(&self) -> Option<bool> {
+        self.dictionary
+    }
+    pub fn bloom_filter(&self) -> Option<bool> {
+        self.bloom_filter
+    }
+}
+
+/// Version of the parquet crate used to write a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriterCrate {
+    #[default]
+    Parquet56,
+    /// parquet 57 (which also has a new thrift encoder for the metadata)
+    Parquet57,
+}
+
+impl Display for WriterCrate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriterCrate::Parquet56 => write!(f, "parquet 56"),
+            WriterCrate::Parquet57 => write!(f, "parquet 57"),
+        }
+    }
+}
+
+/// Distribution of the number of rows in each row group
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowGroupSizes {
+    /// Every row group has `rows_per_row_group` rows
+    #[default]
+    Fixed,
+    /// A random number of rows between `min` and `max` (inclusive), like files
+    /// written by streaming ingestion
+    RandomRange { min: usize, max: usize },
+    /// The first row group has `rows_per_row_group` rows and each following row
+    /// group `ratio` (at most 1) times the rows of the previous one (at least 1)
+    Geometric { ratio: f64 },
+}
+
+impl Display for RowGroupSizes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowGroupSizes::Fixed => write!(f, "fixed size row groups"),
+            RowGroupSizes::RandomRange { min, max } => {
+                write!(f, "{min}-{max} rows per row group")
+            }
+            RowGroupSizes::Geometric { ratio } => {
+                write!(f, "geometric row group sizes (ratio {ratio})")
+            }
+        }
+    }
+}
+
+/// Parquet modular encryption of the file, with the test keys in
+/// [`crate::encryption`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encryption {
+    /// Not encrypted
+    #[default]
+    None,
+    /// Encrypted footer (`PARE` magic), with every column encrypted with the
+    /// footer key
+    EncryptedFooter,
+    /// Plaintext footer with the metadata of every column encrypted with a
+    /// column key
+    PlaintextFooter,
+}
+
+impl Display for Encryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encryption::None => write!(f, "not encrypted"),
+            Encryption::EncryptedFooter => write!(f, "encrypted footer"),
+            Encryption::PlaintextFooter => write!(f, "plaintext footer, encrypted columns"),
+        }
+    }
+}
+
+/// Formats a list of options as `A/B/C`
+pub fn display_list<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected(selection: &ColumnSelection, columns: usize) -> Vec<usize> {
+        (0..columns)
+            .filter(|column| selection.contains(*column))
+            .collect()
+    }
+
+    #[test]
+    fn column_selection_contains() {
+        assert_eq!(selected(&ColumnSelection::All, 4), vec![0, 1, 2, 3]);
+        assert_eq!(selected(&ColumnSelection::First(2), 4), vec![0, 1]);
+        assert_eq!(selected(&ColumnSelection::First(0), 4), Vec::<usize>::new());
+        assert_eq!(selected(&ColumnSelection::EveryNth(1), 4), vec![0, 1, 2, 3]);
+        assert_eq!(selected(&ColumnSelection::EveryNth(3), 8), vec![0, 3, 6]);
+        // every 0th column selects nothing rather than dividing by zero
+        assert_eq!(
+            selected(&ColumnSelection::EveryNth(0), 4),
+            Vec::<usize>::new()
+        );
+        assert_eq!(selected(&ColumnSelection::List(vec![3, 1]), 4), vec![1, 3]);
+    }
+
+    #[test]
+    fn column_selection_display() {
+        assert_eq!(ColumnSelection::EveryNth(1).to_string(), "all cols");
+        assert_eq!(ColumnSelection::EveryNth(2).to_string(), "every 2nd col");
+        assert_eq!(ColumnSelection::EveryNth(3).to_string(), "every 3rd col");
+        assert_eq!(ColumnSelection::EveryNth(11).to_string(), "every 11th col");
+        assert_eq!(ColumnSelection::EveryNth(21).to_string(), "every 21st col");
+        assert_eq!(
+            ColumnSelection::EveryNth(112).to_string(),
+            "every 112th col"
+        );
+    }
+}